@@ -0,0 +1,173 @@
+use axum::{
+    extract::State,
+    http::StatusCode,
+    response::IntoResponse,
+    Json,
+};
+use reqwest::Client;
+use serde_json::Value;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use std::collections::HashMap;
+
+use crate::model::state::AppState;
+use crate::model::test::{
+    TestType, ApiTestConfig, ApiTest, ApiTestResult, create_test_metrics,
+};
+use crate::model::test::api_test::{apply_extraction, evaluate_api_test, failed_api_test, SchemaSource};
+use crate::http::client::{create_insecure_client, create_optimized_client, send_api_request};
+use crate::http::curl::render_redacted_curl;
+use crate::controller::test_common::TestContext;
+use crate::view::response::create_error_response;
+
+// Run a single API test step, turning transport errors into a failed step result
+async fn run_api_test(client: &Client, test: &ApiTest, variables: &HashMap<String, Value>) -> ApiTestResult {
+    let mut test = test.interpolate(variables);
+    if let Some(source) = &test.schema {
+        match source.load().await {
+            Ok(schema) => test.schema = Some(SchemaSource::Inline(schema)),
+            Err(e) => return failed_api_test(&test, Duration::ZERO, e),
+        }
+    }
+    let start_time = Instant::now();
+    let mut result = match send_api_request(client, &test).await {
+        Ok(response) => apply_extraction(evaluate_api_test(&test, &response), &test, &response),
+        Err(e) => failed_api_test(&test, start_time.elapsed(), format!("{:#}", e)),
+    };
+    if !result.passed {
+        result.curl = Some(render_redacted_curl(&test));
+    }
+    result
+}
+
+/// Start an API test suite, executing each test in order; an empty suite is rejected with 400
+pub async fn start_api_test(
+    State(state): State<Arc<AppState>>,
+    Json(config): Json<ApiTestConfig>,
+) -> impl IntoResponse {
+    if config.tests.is_empty() {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(create_error_response::<()>("At least one API test is required".to_string())),
+        ).into_response();
+    }
+
+    let (context, response) = match TestContext::new(state, TestType::Api).await {
+        Ok((context, response)) => (context, response),
+        Err(response) => return response,
+    };
+
+    tokio::spawn(async move {
+        let client = create_optimized_client();
+        // Only built when a test opts out of certificate verification
+        let mut insecure_client: Option<Client> = None;
+        let total_tests = config.tests.len() as u32;
+        let mut durations: Vec<Duration> = Vec::with_capacity(config.tests.len());
+        let mut status_codes: HashMap<u16, u32> = HashMap::new();
+        let mut failed_tests = 0u32;
+        // Variables extracted by earlier steps, scoped to this run
+        let mut variables: HashMap<String, Value> = HashMap::new();
+
+        tracing::info!("API test {} started with {} tests", context.test_id(), total_tests);
+
+        for (index, test) in config.tests.iter().enumerate() {
+            let test_client = if test.insecure {
+                &*insecure_client.get_or_insert_with(create_insecure_client)
+            } else {
+                &client
+            };
+            let result = run_api_test(test_client, test, &variables).await;
+            variables.extend(result.extracted.clone());
+
+            durations.push(Duration::from_secs_f64(result.response_time / 1000.0));
+            if let Some(status) = result.status {
+                *status_codes.entry(status).or_insert(0) += 1;
+            }
+            if !result.passed {
+                failed_tests += 1;
+            }
+
+            let completed = index as u32 + 1;
+            let metrics = create_test_metrics(completed, total_tests, &durations, status_codes.clone(), failed_tests);
+            let progress = (completed as f32 / total_tests as f32) * 100.0;
+
+            context.send_api_result(progress, metrics, result).await;
+        }
+
+        let final_metrics = create_test_metrics(total_tests, total_tests, &durations, status_codes, failed_tests);
+        let final_error = if failed_tests > 0 {
+            Some(format!("{} of {} API tests failed", failed_tests, total_tests))
+        } else {
+            None
+        };
+
+        context.complete_test(final_metrics, final_error).await;
+        tracing::info!("API test {} finished.", context.test_id());
+    });
+
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::http::test_server::{Reply, TestServer};
+    use crate::model::test::{TestResult, TestStatus};
+
+    async fn start(state: &Arc<AppState>, tests: Vec<ApiTest>) -> (StatusCode, Value) {
+        let response = start_api_test(State(Arc::clone(state)), Json(ApiTestConfig { tests })).await.into_response();
+        let status = response.status();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        (status, serde_json::from_slice(&body).unwrap())
+    }
+
+    async fn finished(state: &AppState, id: &str) -> TestResult {
+        loop {
+            let result = state.get_test_result(id).await.unwrap();
+            if matches!(result.status, TestStatus::Completed | TestStatus::Error) {
+                return result;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+    }
+
+    #[tokio::test]
+    async fn test_empty_suite_is_rejected() {
+        let state = Arc::new(AppState::new().0);
+        let (status, body) = start(&state, Vec::new()).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(body["success"], false);
+        assert!(state.get_all_test_results().await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_suite_runs_in_order_and_redacts_failed_requests() {
+        let server = TestServer::start(|request| match request.path.as_str() {
+            "/login" => Reply::ok(r#"{"token": "abc"}"#),
+            _ => Reply::status(500, ""),
+        })
+        .await;
+        let login: ApiTest = serde_json::from_value(serde_json::json!({
+            "name": "Login", "method": "POST", "url": format!("{}/login", server.url), "expected_status": 200,
+            "extract": [{"name": "token", "json_path": "$.token"}]
+        }))
+        .unwrap();
+        let profile: ApiTest = serde_json::from_value(serde_json::json!({
+            "name": "Profile", "method": "GET", "url": format!("{}/me", server.url), "expected_status": 200,
+            "headers": {"Authorization": "Bearer {{token}}"}
+        }))
+        .unwrap();
+
+        let state = Arc::new(AppState::new().0);
+        let (status, body) = start(&state, vec![login, profile]).await;
+        assert_eq!(status, StatusCode::OK);
+        let result = finished(&state, body["id"].as_str().unwrap()).await;
+
+        assert_eq!(result.status, TestStatus::Error);
+        let steps = result.api_results.unwrap();
+        assert!(steps[0].passed && steps[0].curl.is_none());
+        assert_eq!(server.requests()[1].header("authorization"), Some("Bearer abc"));
+        let curl = steps[1].curl.as_deref().unwrap();
+        assert!(curl.contains("Authorization: REDACTED") && !curl.contains("abc"), "{}", curl);
+    }
+}
@@ -0,0 +1,172 @@
+use axum::{
+    extract::State,
+    response::IntoResponse,
+    Json,
+};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tokio::time::Instant;
+
+use crate::controller::test_common::{IncrementalMetrics, TestContext, time_series_interval, validate_target};
+use crate::http::client::stress_test;
+use crate::model::metrics::EngineStats;
+use crate::model::state::AppState;
+use crate::model::test::{
+    create_test_config_from_breakpoint, BreakpointStep, BreakpointTestConfig, RequestOutcome, TestMetrics,
+    TestStatus, TestType,
+};
+
+// How often the aggregator checks whether the engine has started its first step
+const ENGINE_START_POLL: Duration = Duration::from_millis(100);
+
+/// Start a breakpoint test: raise the load step by step until a step breaks the SLO
+pub async fn start_breakpoint_test(
+    State(state): State<Arc<AppState>>,
+    Json(config): Json<BreakpointTestConfig>,
+) -> impl IntoResponse {
+    let (context, response) = match TestContext::new(state, TestType::Breakpoint).await {
+        Ok((context, response)) => (context, response),
+        Err(response) => return response,
+    };
+
+    let test_config = create_test_config_from_breakpoint(&config);
+
+    if let Some(error) = validate_target(&test_config) {
+        context.complete_test(TestMetrics::default(), Some(error)).await;
+        return response;
+    }
+
+    if config.step_duration_secs == 0 || config.max_steps() == 0 {
+        context.complete_test(
+            TestMetrics::default(),
+            Some("Step duration and max steps must be greater than 0 for breakpoint test".to_string()),
+        ).await;
+        return response;
+    }
+
+    if config.start == 0 {
+        context.complete_test(
+            TestMetrics::default(),
+            Some("Start load must be greater than 0 for breakpoint test".to_string()),
+        ).await;
+        return response;
+    }
+
+    let context = Arc::new(context);
+    let step_duration = Duration::from_secs(config.step_duration_secs as u64);
+    let max_steps = config.max_steps();
+
+    tokio::spawn(async move {
+        let (result_tx, mut result_rx) = mpsc::channel::<RequestOutcome>(1024);
+
+        let is_finished = Arc::new(AtomicBool::new(false));
+        let engine_stats = Arc::new(EngineStats::default());
+        let aggregator_stats = Arc::clone(&engine_stats);
+        let aggregator_finished = Arc::clone(&is_finished);
+        let context_clone = Arc::clone(&context);
+
+        let aggregator_handle = tokio::spawn(async move {
+            let mut metrics_agg = IncrementalMetrics::new(Arc::clone(&aggregator_stats));
+            let mut step_agg = IncrementalMetrics::new(Arc::clone(&aggregator_stats));
+            let mut interval_agg = IncrementalMetrics::new(Arc::clone(&aggregator_stats));
+            let mut step_index = 0;
+            let update_interval = Duration::from_millis(500);
+            let mut last_update_time = Instant::now();
+            let mut time_series = time_series_interval();
+
+            tracing::info!("Aggregator task started for breakpoint test {}", context_clone.test_id());
+
+            loop {
+                // Steps run on the engine's clock, which starts once the test plan is prepared
+                let step_end = aggregator_stats.started().map(|started| started + step_duration * (step_index + 1));
+                let outcome = tokio::select! {
+                    outcome = result_rx.recv() => outcome,
+                    _ = time_series.tick() => {
+                        if let Err(e) = context_clone.update_time_series(&interval_agg.take_metrics()).await {
+                            tracing::warn!("Failed to update time series: {}", e);
+                        }
+                        continue;
+                    }
+                    _ = tokio::time::sleep_until(step_end.unwrap_or_else(|| Instant::now() + ENGINE_START_POLL)),
+                        if step_index < max_steps =>
+                    {
+                        if step_end.is_none() {
+                            continue;
+                        }
+                        // Judge the step that just ended and stop at the first one breaking the SLO
+                        let step_metrics = step_agg.take_metrics();
+                        let load = config.load_at(step_index);
+                        let violations = config.slo.violations(&step_metrics);
+                        let passed = violations.is_empty();
+                        step_index += 1;
+                        if passed {
+                            tracing::info!("Breakpoint test {} passed at load {}", context_clone.test_id(), load);
+                        } else {
+                            tracing::info!("Breakpoint test {} broke at load {}: {}", context_clone.test_id(), load, violations.join(", "));
+                            aggregator_finished.store(true, Ordering::SeqCst);
+                        }
+
+                        let progress = (step_index as f32 / max_steps as f32 * 100.0).min(100.0);
+                        let step = BreakpointStep { load, passed, violations, metrics: step_metrics };
+                        context_clone.send_breakpoint_step(progress, metrics_agg.calculate_metrics(), step).await;
+                        if !passed {
+                            step_index = max_steps;
+                        }
+                        continue;
+                    }
+                };
+                let Some(outcome) = outcome else {
+                    break;
+                };
+                metrics_agg.update(&outcome);
+                step_agg.update(&outcome);
+                interval_agg.update(&outcome);
+
+                let now = Instant::now();
+                if now.duration_since(last_update_time) >= update_interval {
+                    let elapsed = aggregator_stats.started().map_or(0.0, |started| started.elapsed().as_secs_f64());
+                    let progress = (elapsed
+                        / (step_duration.as_secs_f64() * max_steps as f64) * 100.0).min(100.0);
+                    let intermediate_metrics = metrics_agg.calculate_metrics();
+                    context_clone.send_update(
+                        TestStatus::Running,
+                        progress as f32,
+                        Some(intermediate_metrics.clone()),
+                        None,
+                    ).await;
+                    last_update_time = now;
+                }
+            }
+
+            // The engine can stop just before the last step boundary; judge that step too
+            if step_index < max_steps {
+                let step_metrics = step_agg.calculate_metrics();
+                let load = config.load_at(step_index);
+                let violations = config.slo.violations(&step_metrics);
+                let step = BreakpointStep { load, passed: violations.is_empty(), violations, metrics: step_metrics };
+                context_clone.send_breakpoint_step(100.0, metrics_agg.calculate_metrics(), step).await;
+            }
+
+            tracing::info!("Aggregator channel closed for breakpoint test {}. Calculating final metrics.", context_clone.test_id());
+            let final_metrics = metrics_agg.calculate_metrics();
+            if let Err(e) = context_clone.update_time_series(&interval_agg.take_metrics()).await {
+                tracing::warn!("Failed to update final time series: {}", e);
+            }
+            context_clone.complete_test(final_metrics, None).await;
+        });
+
+        if let Err(e) = stress_test(&test_config, result_tx, Arc::clone(&is_finished), engine_stats).await {
+            tracing::error!("Failed during stress_test function for breakpoint test {}: {}", context.test_id(), e);
+            is_finished.store(true, Ordering::SeqCst);
+            aggregator_handle.abort();
+
+            let error_msg = format!("Breakpoint test failed during execution: {}", e);
+            context.send_update(TestStatus::Error, 0.0, None, Some(error_msg.clone())).await;
+            context.complete_test(TestMetrics::default(), Some(error_msg)).await;
+        }
+    });
+
+    response
+}
@@ -0,0 +1,154 @@
+use axum::{
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    Json,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::http::curl::{parse_curl, render_curl, render_template_curl};
+use crate::model::error::AppError;
+use crate::model::test::{import_har, HarImportOptions, RequestTemplate};
+use crate::model::test::api_test::{ApiTest, import_openapi, import_postman, parse_document, OpenApiImportOptions};
+use crate::view::response::{create_api_response, create_error_response};
+
+/// Request body for importing an OpenAPI document
+#[derive(Debug, Deserialize)]
+pub struct OpenApiImportRequest {
+    /// The document, either as a JSON object or as JSON/YAML text
+    pub spec: Value,
+    #[serde(flatten)]
+    pub options: OpenApiImportOptions,
+}
+
+/// Request body for importing a Postman collection
+#[derive(Debug, Deserialize)]
+pub struct PostmanImportRequest {
+    /// Collection v2.1 JSON, as an object or as text
+    pub collection: Value,
+    /// Optional Postman environment whose values override collection variables
+    #[serde(default)]
+    pub environment: Option<Value>,
+}
+
+/// Request body for importing a HAR recording
+#[derive(Debug, Deserialize)]
+pub struct HarImportRequest {
+    /// HAR 1.2 document, as an object or as text
+    pub har: Value,
+    #[serde(flatten)]
+    pub options: HarImportOptions,
+}
+
+/// Request body for importing a curl command
+#[derive(Debug, Deserialize)]
+pub struct CurlImportRequest {
+    pub command: String,
+}
+
+/// Request body for exporting an API test or a load/stress request as curl
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+pub enum CurlExportRequest {
+    Test { test: ApiTest },
+    Request { request: RequestTemplate },
+}
+
+/// Response body of a curl export
+#[derive(Debug, Serialize)]
+pub struct CurlExport {
+    pub command: String,
+}
+
+// Accept a document given inline as JSON or as JSON/YAML text
+fn document_from_value(value: Value) -> Result<Value, AppError> {
+    match value {
+        Value::String(content) => parse_document(&content),
+        other => Ok(other),
+    }
+}
+
+fn import_error(error: AppError) -> Response {
+    (
+        StatusCode::BAD_REQUEST,
+        Json(create_error_response::<()>(error.to_string())),
+    ).into_response()
+}
+
+/// Generate an API test suite (and optionally a load scenario) from an OpenAPI 3.x document
+pub async fn import_openapi_spec(
+    Json(request): Json<OpenApiImportRequest>,
+) -> Response {
+    let imported = document_from_value(request.spec)
+        .and_then(|spec| import_openapi(&spec, &request.options));
+
+    match imported {
+        Ok(import) => Json(create_api_response(
+            true,
+            format!("Imported {} API tests from OpenAPI document", import.api_tests.tests.len()),
+            Some(import),
+        )).into_response(),
+        Err(e) => import_error(e),
+    }
+}
+
+/// Convert a Postman collection v2.1 into an API test suite
+pub async fn import_postman_collection(
+    Json(request): Json<PostmanImportRequest>,
+) -> Response {
+    let imported = document_from_value(request.collection).and_then(|collection| {
+        let environment = request.environment.map(document_from_value).transpose()?;
+        import_postman(&collection, environment.as_ref())
+    });
+
+    match imported {
+        Ok(config) => Json(create_api_response(
+            true,
+            format!("Imported {} API tests from Postman collection", config.tests.len()),
+            Some(config),
+        )).into_response(),
+        Err(e) => import_error(e),
+    }
+}
+
+/// Convert a HAR recording into a request sequence for load and stress tests
+pub async fn import_har_recording(
+    Json(request): Json<HarImportRequest>,
+) -> Response {
+    let imported = document_from_value(request.har)
+        .and_then(|har| import_har(&har, &request.options));
+
+    match imported {
+        Ok(import) => Json(create_api_response(
+            true,
+            format!("Imported {} requests from HAR ({} skipped)", import.requests.len(), import.skipped),
+            Some(import),
+        )).into_response(),
+        Err(e) => import_error(e),
+    }
+}
+
+/// Convert a curl command line into an API test
+pub async fn import_curl_command(
+    Json(request): Json<CurlImportRequest>,
+) -> Response {
+    match parse_curl(&request.command) {
+        Ok(test) => Json(create_api_response(
+            true,
+            format!("Imported {} {} from curl command", test.method, test.url),
+            Some(test),
+        )).into_response(),
+        Err(e) => import_error(AppError::InvalidConfig(format!("{:#}", e))),
+    }
+}
+
+/// Render an API test or a load/stress request as a curl command
+pub async fn export_curl_command(
+    Json(request): Json<CurlExportRequest>,
+) -> Response {
+    let command = match &request {
+        CurlExportRequest::Test { test } => render_curl(test),
+        CurlExportRequest::Request { request } => render_template_curl(request),
+    };
+    Json(create_api_response(true, "Rendered curl command".to_string(), Some(CurlExport { command }))).into_response()
+}
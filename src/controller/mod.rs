@@ -6,9 +6,17 @@ mod test_common;
 mod test_operations;
 mod load_test_controller;
 mod stress_test_controller;
+mod breakpoint_test_controller;
+mod api_test_controller;
+mod import_controller;
 
 // Re-export the router for main.rs
 pub use router::create_router;
 pub use load_test_controller::start_load_test;
 pub use stress_test_controller::start_stress_test;
+pub use breakpoint_test_controller::start_breakpoint_test;
+pub use api_test_controller::start_api_test;
+pub use import_controller::{
+    export_curl_command, import_curl_command, import_har_recording, import_openapi_spec, import_postman_collection,
+};
 pub use test_operations::get_all_test_results; 
\ No newline at end of file
@@ -9,6 +9,11 @@ use crate::controller::health::health_check;
 use crate::controller::{
     load_test_controller::start_load_test,
     stress_test_controller::start_stress_test,
+    breakpoint_test_controller::start_breakpoint_test,
+    api_test_controller::start_api_test,
+    import_controller::{
+        export_curl_command, import_curl_command, import_har_recording, import_openapi_spec, import_postman_collection,
+    },
     test_operations::get_all_test_results,
     websocket::handle_ws,
 };
@@ -23,6 +28,15 @@ pub fn create_router(state: Arc<AppState>) -> Router {
         .route("/api/tests", get(get_all_test_results))
         .route("/api/load-test", post(start_load_test))
         .route("/api/stress-test", post(start_stress_test))
+        .route("/api/breakpoint-test", post(start_breakpoint_test))
+        .route("/api/api-test", post(start_api_test))
+        
+        // Import endpoints
+        .route("/api/import/openapi", post(import_openapi_spec))
+        .route("/api/import/postman", post(import_postman_collection))
+        .route("/api/import/har", post(import_har_recording))
+        .route("/api/import/curl", post(import_curl_command))
+        .route("/api/export/curl", post(export_curl_command))
         
         // WebSocket endpoint
         .route("/ws", get(handle_ws))
@@ -6,95 +6,16 @@ use axum::{
 use std::sync::Arc;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::time::{Duration, Instant};
-use std::collections::HashMap;
 use tokio::sync::mpsc;
-use anyhow::Error; // Import anyhow::Error
 
+use crate::model::metrics::EngineStats;
 use crate::model::state::AppState;
 use crate::model::test::{
-    TestType, TestStatus, StressTestConfig, TestConfig, TestMetrics, RequestResult // Import RequestResult here
+    TestType, TestStatus, StressTestConfig, TestMetrics, RequestOutcome
 };
-// Remove RequestResult from http::client import
-use crate::http::client::{create_optimized_client, stress_test};
-use crate::controller::test_common::TestContext;
-// Removed: use std::error::Error;
-
-// Helper struct to accumulate stress test results incrementally
-#[derive(Default, Debug, Clone)]
-struct IncrementalStressMetrics {
-    total_duration: Duration,
-    status_codes: HashMap<u16, u32>,
-    successful_requests: u32,
-    failed_requests: u32,
-    requests_completed: u32,
-    min_response_time: f64,
-    max_response_time: f64,
-    response_time_sum: f64,
-}
-
-impl IncrementalStressMetrics {
-    fn new() -> Self {
-        Self {
-            min_response_time: f64::MAX,
-            ..Default::default()
-        }
-    }
-
-    // Update accumulators based on a single request result (now Result<RequestResult, anyhow::Error>)
-    fn update(&mut self, result: &Result<RequestResult, Error>) { // Use anyhow::Error
-        self.requests_completed += 1;
-        match result {
-            Ok(res) => {
-                self.successful_requests += 1;
-                self.total_duration += res.duration;
-                *self.status_codes.entry(res.status).or_insert(0) += 1;
-
-                let duration_ms = res.duration.as_secs_f64() * 1000.0;
-                self.response_time_sum += duration_ms;
-                if duration_ms < self.min_response_time {
-                    self.min_response_time = duration_ms;
-                }
-                if duration_ms > self.max_response_time {
-                    self.max_response_time = duration_ms;
-                }
-            }
-            Err(_) => {
-                self.failed_requests += 1;
-            }
-        }
-    }
-
-    // Calculate TestMetrics based on accumulated data
-    fn calculate_metrics(&self) -> TestMetrics {
-        let avg_response_time = if self.successful_requests > 0 {
-            self.response_time_sum / self.successful_requests as f64
-        } else {
-            0.0
-        };
-        let rps = if !self.total_duration.is_zero() {
-            self.successful_requests as f64 / self.total_duration.as_secs_f64()
-        } else {
-            0.0
-        };
-        let error_rate = if self.requests_completed > 0 {
-            (self.failed_requests as f64 / self.requests_completed as f64) * 100.0
-        } else {
-            0.0
-        };
-
-        TestMetrics {
-            requests_completed: self.requests_completed,
-            total_requests: self.requests_completed, // For stress test, total = completed
-            average_response_time: avg_response_time,
-            min_response_time: if self.min_response_time == f64::MAX { 0.0 } else { self.min_response_time },
-            max_response_time: self.max_response_time,
-            error_rate,
-            requests_per_second: rps,
-            status_codes: self.status_codes.clone(),
-        }
-    }
-}
-
+use crate::model::test::create_test_config_from_stress;
+use crate::http::client::stress_test;
+use crate::controller::test_common::{IncrementalMetrics, TestContext, time_series_interval, validate_target};
 
 /// Start a stress test (Refactored for Channel Aggregation)
 pub async fn start_stress_test(
@@ -106,12 +27,13 @@ pub async fn start_stress_test(
         Err(response) => return response,
     };
 
-    let test_config = TestConfig {
-        target_url: config.target_url,
-        concurrent_users: config.concurrent_users,
-        duration_secs: config.duration_secs,
-        num_requests: 0,
-    };
+    // Concurrency and duration default to the peak and length of the stage profile
+    let test_config = create_test_config_from_stress(&config);
+
+    if let Some(error) = validate_target(&test_config) {
+        context.complete_test(TestMetrics::default(), Some(error)).await;
+        return response;
+    }
 
      if test_config.duration_secs == 0 {
          context.complete_test(TestMetrics::default(), Some("Duration must be greater than 0 for stress test".to_string())).await;
@@ -122,24 +44,42 @@ pub async fn start_stress_test(
     let test_duration = Duration::from_secs(test_config.duration_secs as u64);
 
     tokio::spawn(async move {
-        let client = create_optimized_client();
-        // Channel now sends Result<RequestResult, anyhow::Error>
-        let (result_tx, mut result_rx) = mpsc::channel::<Result<RequestResult, Error>>(1024);
+        let (result_tx, mut result_rx) = mpsc::channel::<RequestOutcome>(1024);
 
         let is_finished = Arc::new(AtomicBool::new(false));
+        let engine_stats = Arc::new(EngineStats::default());
+        let aggregator_stats = Arc::clone(&engine_stats);
         let context_clone = Arc::clone(&context);
         let start_time = Instant::now();
 
         // --- Spawn Aggregator Task ---
         let aggregator_handle = tokio::spawn(async move {
-            let mut metrics_agg = IncrementalStressMetrics::new();
+            let mut metrics_agg = IncrementalMetrics::new(Arc::clone(&aggregator_stats));
+            // Requests of the current time series interval only
+            let mut interval_agg = IncrementalMetrics::new(aggregator_stats);
             let update_interval = Duration::from_millis(500);
             let mut last_update_time = Instant::now();
+            let mut time_series = time_series_interval();
 
             tracing::info!("Aggregator task started for stress test {}", context_clone.test_id());
 
-            while let Some(result) = result_rx.recv().await {
-                metrics_agg.update(&result);
+            loop {
+                // Points describe their own interval, including the current stage target, and are
+                // recorded on schedule, also while no request completes
+                let outcome = tokio::select! {
+                    outcome = result_rx.recv() => outcome,
+                    _ = time_series.tick() => {
+                        if let Err(e) = context_clone.update_time_series(&interval_agg.take_metrics()).await {
+                            tracing::warn!("Failed to update time series: {}", e);
+                        }
+                        continue;
+                    }
+                };
+                let Some(outcome) = outcome else {
+                    break;
+                };
+                metrics_agg.update(&outcome);
+                interval_agg.update(&outcome);
 
                 let elapsed = start_time.elapsed();
                 let progress = (elapsed.as_secs_f64() / test_duration.as_secs_f64() * 100.0).min(100.0);
@@ -147,32 +87,30 @@ pub async fn start_stress_test(
                 let now = Instant::now();
                  if now.duration_since(last_update_time) >= update_interval {
                     let intermediate_metrics = metrics_agg.calculate_metrics();
-                    // Correctly format the anyhow::Error to String for send_update
-                    let error_string = result.err().map(|e| format!("{:?}", e)); // Use Debug format
+                    let error_string = metrics_agg.describe_errors();
 
                     context_clone.send_update(
                         TestStatus::Running,
                         progress as f32,
-                        Some(intermediate_metrics),
-                        error_string, // Pass formatted error string
+                        Some(intermediate_metrics.clone()),
+                        error_string,
                     ).await;
                     last_update_time = now;
                 }
             }
             tracing::info!("Aggregator channel closed for stress test {}. Calculating final metrics.", context_clone.test_id());
             let final_metrics = metrics_agg.calculate_metrics();
-            let final_error = if metrics_agg.failed_requests > 0 {
-                 Some(format!("{} requests failed", metrics_agg.failed_requests))
-            } else {
-                 None
-            };
+            let final_error = metrics_agg.describe_errors();
             context_clone.send_update(TestStatus::Running, 100.0, Some(final_metrics.clone()), final_error.clone()).await;
+            if let Err(e) = context_clone.update_time_series(&interval_agg.take_metrics()).await {
+                tracing::warn!("Failed to update final time series: {}", e);
+            }
             context_clone.complete_test(final_metrics, final_error).await;
             tracing::info!("Aggregator task finished for stress test {}.", context_clone.test_id());
         });
 
         // --- Start the stress test execution ---
-        if let Err(e) = stress_test(&client, &test_config, result_tx, Arc::clone(&is_finished)).await {
+        if let Err(e) = stress_test(&test_config, result_tx, Arc::clone(&is_finished), engine_stats).await {
              tracing::error!("Failed during stress_test function for test {}: {}", context.test_id(), e);
             is_finished.store(true, Ordering::SeqCst);
             aggregator_handle.abort();
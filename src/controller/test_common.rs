@@ -2,9 +2,163 @@ use axum::{
     response::{IntoResponse, Response},
     Json,
 };
+use chrono::Utc;
+use std::collections::HashMap;
 use std::sync::Arc;
+use std::sync::atomic::Ordering;
+use std::time::Duration;
+use tokio::time::{Instant, Interval, MissedTickBehavior};
+use crate::http::error_class::{classify_error, error_message};
+use crate::model::failure::ErrorBreakdown;
+use crate::model::histogram::LatencyHistogram;
+use crate::model::metrics::EngineStats;
+use crate::model::phase::PhaseHistograms;
 use crate::model::state::AppState;
-use crate::model::test::{TestType, TestStatus, TestResult, TestMetrics, TestUpdate};
+use crate::model::test::{TestType, TestStatus, TestResult, TestMetrics, TestUpdate, TestConfig, ApiTestResult, ThinkTime, BreakpointReport, BreakpointStep, SuccessCriteria, RequestOutcome, RequestResult};
+
+/// Length of the interval each time series point describes
+pub const TIME_SERIES_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Timer for the time series of a running test, first ticking one interval from now
+pub fn time_series_interval() -> Interval {
+    let mut interval = tokio::time::interval_at(Instant::now() + TIME_SERIES_INTERVAL, TIME_SERIES_INTERVAL);
+    interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+    interval
+}
+
+/// Accumulates load, stress and breakpoint results into `TestMetrics`
+#[derive(Default, Debug, Clone)]
+pub struct IncrementalMetrics {
+    /// When counting started; throughput is measured against wall-clock time from here
+    started: Option<Instant>,
+    status_codes: HashMap<u16, u32>,
+    successful_requests: u32,
+    failed_requests: u32,
+    /// Responses that broke the success policy, part of `failed_requests`
+    http_failures: u32,
+    /// Failed requests by error class, with the most common messages
+    errors: ErrorBreakdown,
+    requests_completed: u32,
+    /// Response times of every response received, successful or not
+    response_times: LatencyHistogram,
+    /// Connection and transfer phases of every phase-timed response
+    phases: PhaseHistograms,
+    /// Accumulators of each weighted scenario endpoint
+    endpoints: HashMap<Arc<str>, IncrementalMetrics>,
+    /// Engine counters and target load, reported by the overall accumulator only
+    engine_stats: Option<Arc<EngineStats>>,
+}
+
+impl IncrementalMetrics {
+    pub fn new(engine_stats: Arc<EngineStats>) -> Self {
+        Self {
+            started: Some(Instant::now()),
+            engine_stats: Some(engine_stats),
+            ..Default::default()
+        }
+    }
+
+    /// Metrics of everything recorded so far, then start counting a new interval from now
+    pub fn take_metrics(&mut self) -> TestMetrics {
+        let interval = std::mem::replace(self, Self {
+            started: Some(Instant::now()),
+            engine_stats: self.engine_stats.clone(),
+            ..Default::default()
+        });
+        interval.calculate_metrics()
+    }
+
+    /// Update the totals and, for scenario endpoints, the endpoint's own accumulators
+    pub fn update(&mut self, outcome: &RequestOutcome) {
+        self.record(&outcome.result);
+        if let Some(endpoint) = &outcome.endpoint {
+            let started = self.started;
+            self.endpoints
+                .entry(Arc::clone(endpoint))
+                .or_insert_with(|| Self { started, ..Default::default() })
+                .record(&outcome.result);
+        }
+    }
+
+    fn record(&mut self, result: &anyhow::Result<RequestResult>) {
+        self.requests_completed += 1;
+        match result {
+            Ok(res) => {
+                if let Some(failure) = &res.failure {
+                    self.failed_requests += 1;
+                    self.http_failures += 1;
+                    self.errors.record(failure.class, &failure.message, None, Utc::now());
+                } else {
+                    self.successful_requests += 1;
+                }
+                *self.status_codes.entry(res.status).or_insert(0) += 1;
+                self.response_times.record(res.duration);
+                if let Some(phases) = &res.phases {
+                    self.phases.record(phases);
+                }
+            }
+            Err(e) => {
+                self.failed_requests += 1;
+                self.errors.record(classify_error(e), &error_message(e), Some(&e.to_string()), Utc::now());
+            }
+        }
+    }
+
+    /// Metrics of the recorded requests, with `total_requests` set to the requests completed
+    pub fn calculate_metrics(&self) -> TestMetrics {
+        let elapsed = self.started.map_or(0.0, |started| started.elapsed().as_secs_f64());
+        let rps = if elapsed > 0.0 {
+            self.requests_completed as f64 / elapsed
+        } else {
+            0.0
+        };
+        let error_rate = if self.requests_completed > 0 {
+            (self.failed_requests as f64 / self.requests_completed as f64) * 100.0
+        } else {
+            0.0
+        };
+
+        let engine_stats = self.engine_stats.as_deref();
+
+        TestMetrics {
+            requests_completed: self.requests_completed,
+            total_requests: self.requests_completed,
+            average_response_time: self.response_times.mean_ms(),
+            min_response_time: self.response_times.min_ms(),
+            max_response_time: self.response_times.max_ms(),
+            percentiles: self.response_times.percentiles(),
+            phases: self.phases.metrics(),
+            error_rate,
+            http_failures: self.http_failures,
+            network_failures: self.failed_requests - self.http_failures,
+            error_classes: self.errors.classes().clone(),
+            top_errors: self.errors.top_errors(),
+            requests_per_second: rps,
+            status_codes: self.status_codes.clone(),
+            endpoints: self.endpoint_metrics(),
+            dropped_iterations: engine_stats.map_or(0, |stats| stats.dropped_iterations.load(Ordering::Relaxed)),
+            late_iterations: engine_stats.map_or(0, |stats| stats.late_iterations.load(Ordering::Relaxed)),
+            target_load: engine_stats.map(EngineStats::target_load),
+            active_users: engine_stats.map(|stats| stats.active_users.load(Ordering::Relaxed)),
+        }
+    }
+
+    /// Per-class failure summary, `None` while nothing failed
+    pub fn describe_errors(&self) -> Option<String> {
+        self.errors.describe()
+    }
+
+    // Per-endpoint metrics, each planned as exactly the requests it received
+    fn endpoint_metrics(&self) -> Option<HashMap<String, TestMetrics>> {
+        if self.endpoints.is_empty() {
+            return None;
+        }
+        Some(self.endpoints
+            .iter()
+            .map(|(name, endpoint)| (name.to_string(), endpoint.calculate_metrics()))
+            .collect())
+    }
+}
 
 /// Common test context for managing test state and updates
 pub struct TestContext {
@@ -31,6 +185,8 @@ impl TestContext {
             error: None,
             start_time: chrono::Utc::now(),
             end_time: None,
+            api_results: None,
+            breakpoint: None,
         };
         
         // Add to state
@@ -64,6 +220,38 @@ impl TestContext {
         progress: f32,
         metrics: Option<TestMetrics>,
         error: Option<String>,
+    ) {
+        self.dispatch_update(status, progress, metrics, error, None, None).await;
+    }
+
+    /// Send a test update carrying the outcome of a single API test step
+    pub async fn send_api_result(
+        &self,
+        progress: f32,
+        metrics: TestMetrics,
+        api_result: ApiTestResult,
+    ) {
+        self.dispatch_update(TestStatus::Running, progress, Some(metrics), None, Some(api_result), None).await;
+    }
+
+    /// Send a test update carrying a finished breakpoint test load step
+    pub async fn send_breakpoint_step(
+        &self,
+        progress: f32,
+        metrics: TestMetrics,
+        step: BreakpointStep,
+    ) {
+        self.dispatch_update(TestStatus::Running, progress, Some(metrics), None, None, Some(step)).await;
+    }
+
+    async fn dispatch_update(
+        &self,
+        status: TestStatus,
+        progress: f32,
+        metrics: Option<TestMetrics>,
+        error: Option<String>,
+        api_result: Option<ApiTestResult>,
+        breakpoint_step: Option<BreakpointStep>,
     ) {
         let update = TestUpdate {
             id: self.test_id.clone(),
@@ -72,6 +260,8 @@ impl TestContext {
             progress,
             metrics: metrics.clone(),
             error: error.clone(),
+            api_result: api_result.clone(),
+            breakpoint_step: breakpoint_step.clone(),
         };
         
         // Send update through broadcast channel
@@ -85,11 +275,16 @@ impl TestContext {
             result.progress = progress;
             result.metrics = metrics;
             result.error = error;
+            if let Some(api_result) = api_result {
+                result.api_results.get_or_insert_with(Vec::new).push(api_result);
+            }
+            if let Some(step) = breakpoint_step {
+                result.breakpoint.get_or_insert_with(BreakpointReport::default).record(step);
+            }
             if status == TestStatus::Completed || status == TestStatus::Error {
                 result.end_time = Some(chrono::Utc::now());
             }
-            // Use update_test_result method if available, otherwise add (which might duplicate)
-            // Assuming add_test_result handles updates correctly (e.g., replaces based on ID)
+            // add_test_result replaces the stored result with the same ID
             self.state.add_test_result(result).await;
         } else {
              tracing::warn!("Could not find test result {} to update state.", self.test_id);
@@ -112,4 +307,86 @@ impl TestContext {
     pub async fn update_time_series(&self, metrics: &TestMetrics) -> Result<(), crate::model::error::AppError> {
         self.state.update_time_series(metrics).await
     }
-}
\ No newline at end of file
+}
+
+/// Check that a load/stress config can run, returning the first problem found
+pub fn validate_target(config: &TestConfig) -> Option<String> {
+    let has_sequence = config.requests.as_ref().is_some_and(|requests| !requests.is_empty());
+    let has_scenario = config.scenario.as_ref().is_some_and(|scenario| !scenario.is_empty());
+    let has_sitemap = config.sitemap.is_some();
+    if [has_sequence, has_scenario, has_sitemap].iter().filter(|&&set| set).count() > 1 {
+        return Some("Use only one of a requests sequence, a weighted scenario or a sitemap".to_string());
+    }
+    if has_scenario && config.scenario.iter().flatten().all(|endpoint| endpoint.weight == 0) {
+        return Some("At least one scenario endpoint needs a weight greater than 0".to_string());
+    }
+    if config.target_url.is_empty() && !has_sequence && !has_scenario && !has_sitemap {
+        return Some("Either target_url, a requests sequence, a weighted scenario or a sitemap is required".to_string());
+    }
+    if config.concurrent_users == 0 && config.stages.is_none() && config.arrival_rate.is_none() {
+        return Some("concurrent_users must be greater than 0 unless stages or arrival_rate is set".to_string());
+    }
+    if let Some(Err(error)) = config.think_time.as_ref().map(ThinkTime::validate) {
+        return Some(error);
+    }
+    if config.stages.as_ref().is_some_and(|stages| stages.is_empty()) {
+        return Some("stages needs at least one stage".to_string());
+    }
+    let valid_rate = |rate: f64| rate.is_finite() && rate > 0.0;
+    if config.stages.is_none() && config.arrival_rate.as_ref().is_some_and(|arrival| !valid_rate(arrival.rate)) {
+        return Some("arrival_rate.rate must be a number greater than 0".to_string());
+    }
+    if let Some(Err(error)) = config.success.as_ref().map(SuccessCriteria::new) {
+        return Some(error.to_string());
+    }
+    if let Some(adaptive) = &config.adaptive {
+        if config.stages.is_some() || config.arrival_rate.is_some() {
+            return Some("adaptive cannot be combined with stages or arrival_rate".to_string());
+        }
+        if let Err(error) = adaptive.validate(config.concurrent_users) {
+            return Some(error);
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn outcome(endpoint: Option<&str>, result: anyhow::Result<RequestResult>) -> RequestOutcome {
+        RequestOutcome { endpoint: endpoint.map(Arc::from), result }
+    }
+
+    fn response(ms: u64) -> anyhow::Result<RequestResult> {
+        Ok(RequestResult { status: 200, duration: Duration::from_millis(ms), failure: None, phases: None })
+    }
+
+    #[test]
+    fn test_take_metrics_starts_a_new_interval() {
+        let stats = Arc::new(EngineStats::default());
+        stats.set_target_load(5.0);
+        let mut interval = IncrementalMetrics::new(stats);
+        interval.update(&outcome(Some("home"), response(10)));
+        interval.update(&outcome(None, response(30)));
+        interval.update(&outcome(None, Err(anyhow::anyhow!("connection refused"))));
+
+        let first = interval.take_metrics();
+        assert_eq!(first.requests_completed, 3);
+        assert!((first.average_response_time - 20.0).abs() < 0.1);
+        assert_eq!(first.error_classes.values().sum::<u32>(), 1);
+        assert!(first.endpoints.is_some_and(|endpoints| endpoints["home"].requests_completed == 1));
+
+        let empty = interval.take_metrics();
+        assert_eq!(empty.requests_completed, 0);
+        assert!(empty.error_classes.is_empty() && empty.top_errors.is_empty());
+        assert!(empty.endpoints.is_none_or(|endpoints| endpoints.is_empty()));
+        // Engine counters are still reported after the reset
+        assert_eq!(empty.target_load, Some(5.0));
+
+        interval.update(&outcome(None, response(50)));
+        let next = interval.take_metrics();
+        assert_eq!(next.requests_completed, 1);
+        assert!((next.max_response_time - 50.0).abs() < 0.1);
+    }
+}
@@ -1,15 +1,25 @@
-use reqwest::{Client, Method};
+use reqwest::{Body, Client, ClientBuilder, Method, Request, StatusCode};
+use reqwest::cookie::{CookieStore, Jar};
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue, AUTHORIZATION, CONTENT_LENGTH, CONTENT_TYPE, COOKIE, LOCATION, SET_COOKIE};
+use std::path::Path;
+use std::collections::HashMap;
 use std::sync::Arc;
 use tokio::sync::mpsc;
 use std::sync::atomic::{AtomicBool, Ordering};
-use futures::{stream, StreamExt, Stream};
 use std::time::Duration;
 use anyhow::{Context, Result};
-use std::pin::Pin;
+use bytes::Bytes;
+use serde_json::Value;
+use url::{form_urlencoded, Url};
 
+use crate::http::engine::perform_test;
+use crate::http::timing::TimedClient;
 use crate::model::error::AppError;
-use crate::model::test::{TestConfig};
-use crate::model::test::{ApiTest, RequestResult, ApiRequestResult};
+use crate::model::metrics::EngineStats;
+use crate::model::phase::PhaseTimings;
+use crate::model::utils::fixture::read_fixture;
+use crate::model::test::{SuccessCriteria, TestConfig};
+use crate::model::test::{ApiTest, JsonBody, RequestBody, RequestOutcome, RequestResult, RequestTemplate, ApiRequestResult};
 
 // string_to_method remains the same
 pub fn string_to_method(method: &str) -> Result<Method> {
@@ -25,95 +35,329 @@ pub fn string_to_method(method: &str) -> Result<Method> {
     }
 }
 
-// create_optimized_client remains the same
-pub fn create_optimized_client() -> Client {
+fn optimized_client_builder() -> ClientBuilder {
     Client::builder()
         .pool_max_idle_per_host(10)
         .tcp_keepalive(Some(Duration::from_secs(60)))
         .tcp_nodelay(true)
         .connect_timeout(Duration::from_secs(10))
         .timeout(Duration::from_secs(30))
+}
+
+// create_optimized_client remains the same
+pub fn create_optimized_client() -> Client {
+    optimized_client_builder()
         .build()
         .expect("Failed to create HTTP client")
 }
 
-// send_request remains the same
-pub async fn send_request(client: &Client, url: &str) -> Result<RequestResult> {
-    let start_time = std::time::Instant::now();
-    let response = client.get(url).send()
-        .await
-        .with_context(|| format!("Failed to send request to {}", url))?;
+// Same as create_optimized_client, but without TLS certificate verification (`curl -k`)
+pub fn create_insecure_client() -> Client {
+    optimized_client_builder()
+        .danger_accept_invalid_certs(true)
+        .build()
+        .expect("Failed to create HTTP client")
+}
 
-    let status = response.status().as_u16();
-    let duration = start_time.elapsed();
-    let _ = response.bytes().await.context("Failed to read response body")?;
+/// A request template built once per test and cloned for every send
+#[derive(Debug)]
+pub struct PreparedRequest {
+    request: Request,
+    /// Content of a file body, read once and reused whenever the template is rendered again
+    file_content: Option<Bytes>,
+    pub(crate) think_time: Option<Duration>,
+    /// Scenario endpoint name reported with every result
+    pub(crate) endpoint: Option<Arc<str>>,
+    /// Source template, rendered with feeder data and user variables when the test has them
+    pub(crate) template: RequestTemplate,
+}
 
-    Ok(RequestResult {
-        status,
-        duration,
+/// Resolve a template once: parse the method, append query parameters and read file bodies
+pub async fn prepare_request(template: &RequestTemplate) -> Result<PreparedRequest> {
+    let file_content = match &template.body {
+        Some(RequestBody::File(file)) => Some(read_body_file(&file.path).await?),
+        _ => None,
+    };
+    Ok(PreparedRequest {
+        request: build_template_request(template, file_content.clone())?,
+        file_content,
+        think_time: template.think_time_ms.filter(|ms| *ms > 0).map(Duration::from_millis),
+        endpoint: None,
+        template: template.clone(),
     })
 }
 
+impl PreparedRequest {
+    /// The request with the `{{name}}` placeholders of its template filled from `vars`; a file
+    /// body keeps the content read when the request was prepared
+    pub fn render(&self, vars: &HashMap<String, Value>) -> Result<PreparedRequest> {
+        let template = self.template.interpolate(vars);
+        Ok(PreparedRequest {
+            request: build_template_request(&template, self.file_content.clone())?,
+            file_content: self.file_content.clone(),
+            think_time: self.think_time,
+            endpoint: self.endpoint.clone(),
+            template,
+        })
+    }
+}
 
-// send_api_request remains the same
-pub async fn send_api_request(client: &Client, test: &ApiTest) -> Result<ApiRequestResult> {
-    let start_time = std::time::Instant::now();
+fn build_template_request(template: &RequestTemplate, file_content: Option<Bytes>) -> Result<Request> {
+    build_request(
+        &template.method,
+        &template.url,
+        template.query_params.as_ref(),
+        template.headers.as_ref(),
+        template.body.as_ref(),
+        file_content.unwrap_or_default(),
+    )
+}
 
-    let method = string_to_method(&test.method)?;
-    let mut request_builder = client.request(method, &test.url);
+// Build a request; a file body sends `file_content`, read beforehand. Content-Type is only
+// inferred from the body when the headers do not set it.
+fn build_request(
+    method: &str,
+    url: &str,
+    query_params: Option<&HashMap<String, String>>,
+    headers: Option<&HashMap<String, String>>,
+    body: Option<&RequestBody>,
+    file_content: Bytes,
+) -> Result<Request> {
+    let invalid = || format!("Invalid {} request to {}", method, url);
+    let mut parsed_url = Url::parse(url).with_context(invalid)?;
+    if let Some(query_params) = query_params.filter(|params| !params.is_empty()) {
+        parsed_url.query_pairs_mut().extend_pairs(query_params);
+    }
+    let mut request = Request::new(string_to_method(method)?, parsed_url);
 
-    if let Some(headers) = &test.headers {
-        for (key, value) in headers {
-            request_builder = request_builder.header(key, value);
+    for (key, value) in headers.into_iter().flatten() {
+        let name = HeaderName::from_bytes(key.as_bytes()).with_context(invalid)?;
+        let value = HeaderValue::from_str(value).with_context(invalid)?;
+        request.headers_mut().append(name, value);
+    }
+
+    let (content, content_type) = match body {
+        None => return Ok(request),
+        Some(RequestBody::Text(text)) => (Bytes::from(text.clone()), None),
+        Some(RequestBody::Json(value)) | Some(RequestBody::WrappedJson(JsonBody { json: value })) => {
+            (Bytes::from(serde_json::to_vec(value)?), Some("application/json"))
+        }
+        Some(RequestBody::Form(form)) => {
+            let encoded = form_urlencoded::Serializer::new(String::new()).extend_pairs(&form.form).finish();
+            (Bytes::from(encoded), Some("application/x-www-form-urlencoded"))
+        }
+        Some(RequestBody::File(file)) => {
+            let is_json = Path::new(&file.path)
+                .extension()
+                .is_some_and(|ext| ext.eq_ignore_ascii_case("json"));
+            (file_content, is_json.then_some("application/json"))
+        }
+    };
+    if let Some(content_type) = content_type {
+        if !request.headers().contains_key(CONTENT_TYPE) {
+            request.headers_mut().insert(CONTENT_TYPE, HeaderValue::from_static(content_type));
         }
     }
+    *request.body_mut() = Some(Body::from(content));
+    Ok(request)
+}
 
-    if let Some(body) = &test.body {
-        request_builder = request_builder.body(body.clone());
+// Redirects followed for a session with cookies, as reqwest's default policy does
+const MAX_REDIRECTS: usize = 10;
+
+// The request to send after a redirect response, as browsers do: 307/308 repeat the request,
+// other redirects become a GET without body. Credentials and cookies are dropped when the host
+// changes.
+fn follow_redirect(previous: Request, status: StatusCode, headers: &HeaderMap) -> Option<Request> {
+    let location = headers.get(LOCATION)?.to_str().ok()?;
+    let url = previous.url().join(location).ok()?;
+    let same_host = url.host_str() == previous.url().host_str();
+
+    let mut next = match status {
+        StatusCode::TEMPORARY_REDIRECT | StatusCode::PERMANENT_REDIRECT => previous,
+        _ => {
+            let method = if previous.method() == Method::HEAD { Method::HEAD } else { Method::GET };
+            let mut request = Request::new(method, url.clone());
+            *request.headers_mut() = previous.headers().clone();
+            request.headers_mut().remove(CONTENT_TYPE);
+            request.headers_mut().remove(CONTENT_LENGTH);
+            request
+        }
+    };
+    *next.url_mut() = url;
+    if !same_host {
+        next.headers_mut().remove(AUTHORIZATION);
+        next.headers_mut().remove(COOKIE);
     }
+    Some(next)
+}
+
+// Send `cookies` along with any Cookie header the request already has
+fn add_cookies(request: &mut Request, cookies: HeaderValue) {
+    let combined = match request.headers().get(COOKIE) {
+        Some(existing) => {
+            let mut combined = existing.as_bytes().to_vec();
+            combined.extend_from_slice(b"; ");
+            combined.extend_from_slice(cookies.as_bytes());
+            HeaderValue::from_bytes(&combined).unwrap_or(cookies)
+        }
+        None => cookies,
+    };
+    request.headers_mut().insert(COOKIE, combined);
+}
+
+/// Send a prepared request for a virtual user over the phase-timed `client`, timed until the
+/// final response headers, and judge the response with `success`.
+///
+/// Redirects are followed here, so that a cookie jar gets its cookies attached to and stored from
+/// every hop; the phase timings add up all hops. The response headers and body are kept when
+/// `capture` is set.
+pub async fn send_session_request(
+    client: &TimedClient,
+    prepared: &PreparedRequest,
+    jar: Option<&Jar>,
+    success: &SuccessCriteria,
+    capture: bool,
+) -> Result<(RequestResult, Option<ApiRequestResult>)> {
+    let mut request = prepared.request
+        .try_clone()
+        .context("Request body cannot be replayed")?;
+    let (method, url) = (request.method().clone(), request.url().clone());
+
+    let start_time = std::time::Instant::now();
+    let mut phases = PhaseTimings::default();
+    let mut redirects = 0;
+    // The request's own Cookie header; jar cookies are picked again for every hop
+    let mut own_cookies = request.headers().get(COOKIE).cloned();
+    let response = loop {
+        if let Some(jar) = jar {
+            match &own_cookies {
+                Some(cookies) => request.headers_mut().insert(COOKIE, cookies.clone()),
+                None => request.headers_mut().remove(COOKIE),
+            };
+            if let Some(cookies) = jar.cookies(request.url()) {
+                add_cookies(&mut request, cookies);
+            }
+        }
+        let response = client.execute(&request)
+            .await
+            .with_context(|| format!("Failed to send {} request to {}", method, url))?;
+        phases.add(&response.phases);
+        if let Some(jar) = jar {
+            jar.set_cookies(&mut response.headers.get_all(SET_COOKIE).iter(), request.url());
+        }
 
-    let response = request_builder.send()
+        if !response.status.is_redirection() || redirects == MAX_REDIRECTS {
+            break response;
+        }
+        match follow_redirect(request, response.status, &response.headers) {
+            Some(next) => {
+                if !next.headers().contains_key(COOKIE) {
+                    own_cookies = None;
+                }
+                request = next;
+                redirects += 1;
+            }
+            None => break response,
+        }
+    };
+
+    // Response time runs until the final response headers, as with reqwest
+    let duration = start_time.elapsed().saturating_sub(response.phases.download);
+    let status = response.status.as_u16();
+    let body = if capture || success.needs_body() {
+        String::from_utf8_lossy(&response.body).into_owned()
+    } else {
+        String::new()
+    };
+    let failure = success.check(status, duration, &body);
+    let captured = capture.then(|| {
+        let json = serde_json::from_str::<serde_json::Value>(&body).ok();
+        ApiRequestResult { status, duration, json, headers: collect_headers(&response.headers), body }
+    });
+
+    Ok((RequestResult { status, duration, failure, phases: Some(phases) }, captured))
+}
+
+// Read a file body from the fixtures directory
+async fn read_body_file(path: &str) -> Result<Bytes> {
+    let content = read_fixture(path)
+        .await
+        .context("Failed to read request body file")?;
+    Ok(Bytes::from(content))
+}
+
+// Check whether a header map contains a header, ignoring case
+pub fn has_header(headers: &HashMap<String, String>, name: &str) -> bool {
+    headers.keys().any(|key| key.eq_ignore_ascii_case(name))
+}
+
+// Flatten response headers into a map, joining repeated headers with ", "
+fn collect_headers(headers: &HeaderMap) -> HashMap<String, String> {
+    let mut collected: HashMap<String, String> = HashMap::new();
+    for (name, value) in headers {
+        let value = String::from_utf8_lossy(value.as_bytes()).into_owned();
+        collected
+            .entry(name.as_str().to_string())
+            .and_modify(|existing| {
+                existing.push_str(", ");
+                existing.push_str(&value);
+            })
+            .or_insert(value);
+    }
+    collected
+}
+
+// send_api_request keeps headers and the raw body for assertions
+pub async fn send_api_request(client: &Client, test: &ApiTest) -> Result<ApiRequestResult> {
+    let file_content = match &test.body {
+        Some(RequestBody::File(file)) => read_body_file(&file.path).await?,
+        _ => Bytes::new(),
+    };
+    let request = build_request(&test.method, &test.url, None, test.headers.as_ref(), test.body.as_ref(), file_content)?;
+
+    let start_time = std::time::Instant::now();
+    let response = client.execute(request)
         .await
         .with_context(|| format!("Failed to send API request to {}", test.url))?;
 
     let duration = start_time.elapsed();
     let status = response.status().as_u16();
+    let headers = collect_headers(response.headers());
 
-    let json_body = if response.status().is_success() {
-         response.json::<serde_json::Value>().await.ok()
-    } else {
-         let _ = response.text().await.context("Failed to read error response body")?;
-         None
-    };
+    let body = response.text().await.context("Failed to read API response body")?;
+    let json_body = serde_json::from_str::<serde_json::Value>(&body).ok();
 
     Ok(ApiRequestResult {
         status,
         duration,
         json: json_body,
+        headers,
+        body,
     })
 }
 
 
 // load_test remains the same
 pub async fn load_test(
-    client: &Client,
     config: &TestConfig,
-    result_sender: mpsc::Sender<Result<RequestResult>>,
+    result_sender: mpsc::Sender<RequestOutcome>,
     is_finished: Arc<AtomicBool>,
+    stats: Arc<EngineStats>,
 ) -> Result<(), AppError> {
     tracing::info!("Starting load test: {} requests, {} concurrent users",
         config.num_requests, config.concurrent_users);
-    perform_test(client, config, result_sender, is_finished)
+    perform_test(config, result_sender, is_finished, stats)
         .await
         .map_err(|e| AppError::TestExecutionError(format!("Load test execution failed: {}", e)))
 }
 
 // stress_test remains the same
 pub async fn stress_test(
-    client: &Client,
     config: &TestConfig,
-    result_sender: mpsc::Sender<Result<RequestResult>>,
+    result_sender: mpsc::Sender<RequestOutcome>,
     is_finished: Arc<AtomicBool>,
+    stats: Arc<EngineStats>,
 ) -> Result<(), AppError> {
     tracing::info!("Starting stress test: {} seconds, {} concurrent users",
         config.duration_secs, config.concurrent_users);
@@ -122,7 +366,7 @@ pub async fn stress_test(
     let result_sender_clone = result_sender.clone();
 
     let test_result = tokio::select! {
-        res = perform_test(client, config, result_sender_clone, Arc::clone(&is_finished)) => res,
+        res = perform_test(config, result_sender_clone, Arc::clone(&is_finished), stats) => res,
         _ = tokio::time::sleep_until(end_time) => {
             tracing::info!("Stress test duration reached");
             is_finished.store(true, Ordering::SeqCst);
@@ -133,38 +377,146 @@ pub async fn stress_test(
     test_result.map_err(|e| AppError::TestExecutionError(format!("Stress test execution failed: {}", e)))
 }
 
-// perform_test updated take_while closure
-async fn perform_test(
-    client: &Client,
-    config: &TestConfig,
-    result_sender: mpsc::Sender<Result<RequestResult>>,
-    is_finished: Arc<AtomicBool>,
-) -> Result<()> {
-    let stream_iter: Pin<Box<dyn Stream<Item = ()> + Send>> = if config.num_requests > 0 {
-        Box::pin(stream::iter(std::iter::repeat(()).take(config.num_requests as usize)))
-    } else {
-        Box::pin(stream::iter(std::iter::repeat(())))
-    };
 
-    stream_iter
-        .map(|_| {
-            let url = config.target_url.clone();
-            let client = client.clone();
-            let sender = result_sender.clone();
-            async move {
-                let result = send_request(&client, &url).await;
-                sender.send(result).await.is_ok()
-            }
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::http::test_server::{Reply, TestServer};
+    use serde_json::json;
+
+    async fn prepare(template: serde_json::Value) -> PreparedRequest {
+        let template: RequestTemplate = serde_json::from_value(template).unwrap();
+        prepare_request(&template).await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_session_request_redirects() {
+        let server = TestServer::start(|request| match request.path.as_str() {
+            "/see-other" => Reply::redirect(303, "/landing"),
+            "/temporary" => Reply::redirect(307, "/landing"),
+            _ => Reply::ok("landed"),
         })
-        .buffer_unordered(config.concurrent_users as usize)
-        .take_while(|send_success| {
-            let success = *send_success;
-            let finished = is_finished.load(Ordering::SeqCst);
-            async move { success && !finished }
+        .await;
+        let client = TimedClient::new().unwrap();
+        let success = SuccessCriteria::default();
+
+        for path in ["/see-other", "/temporary"] {
+            let prepared = prepare(json!({
+                "method": "POST",
+                "url": format!("{}{}", server.url, path),
+                "headers": { "Content-Type": "text/plain" },
+                "body": "payload"
+            }))
+            .await;
+            let (result, _) = send_session_request(&client, &prepared, None, &success, false).await.unwrap();
+            assert_eq!(result.status, 200);
+        }
+
+        let requests = server.requests();
+        let landed: Vec<_> = requests.iter().filter(|request| request.path == "/landing").collect();
+        // 303 turns the POST into a GET without body
+        assert_eq!(landed[0].method, "GET");
+        assert!(landed[0].body.is_empty());
+        assert_eq!(landed[0].header("content-type"), None);
+        // 307 repeats the request as is
+        assert_eq!(landed[1].method, "POST");
+        assert_eq!(landed[1].body, b"payload");
+        assert_eq!(landed[1].header("content-type"), Some("text/plain"));
+    }
+
+    #[tokio::test]
+    async fn test_session_request_carries_cookies_across_redirects() {
+        let server = TestServer::start(|request| match request.path.as_str() {
+            "/login" => Reply::redirect(302, "/home").header("Set-Cookie", "session=abc; Path=/"),
+            "/elsewhere" => Reply::redirect(302, request.header("x-other-host").unwrap_or_default()),
+            _ => Reply::ok("home"),
         })
-        .for_each(|_| async {})
         .await;
+        let client = TimedClient::new().unwrap();
+        let success = SuccessCriteria::default();
+        let jar = Jar::default();
+
+        let login = prepare(json!({
+            "url": format!("{}/login", server.url),
+            "headers": { "Cookie": "theme=dark" }
+        }))
+        .await;
+        let (result, _) = send_session_request(&client, &login, Some(&jar), &success, false).await.unwrap();
+        assert_eq!(result.status, 200);
+        let home = prepare(json!({ "url": format!("{}/home", server.url) })).await;
+        send_session_request(&client, &home, Some(&jar), &success, false).await.unwrap();
+
+        let requests = server.requests();
+        assert_eq!(requests[0].header("cookie"), Some("theme=dark"));
+        assert_eq!(requests[1].path, "/home");
+        assert_eq!(requests[1].header("cookie"), Some("theme=dark; session=abc"));
+        assert_eq!(requests[2].header("cookie"), Some("session=abc"));
+
+        // Without a jar only the request's own cookies are sent, to the same host
+        send_session_request(&client, &login, None, &success, false).await.unwrap();
+        assert_eq!(server.requests()[4].header("cookie"), Some("theme=dark"));
+        let other_host = prepare(json!({
+            "url": format!("{}/elsewhere", server.url),
+            "headers": { "Cookie": "theme=dark", "X-Other-Host": server.url.replace("127.0.0.1", "localhost") }
+        }))
+        .await;
+        send_session_request(&client, &other_host, Some(&jar), &success, false).await.unwrap();
+        let requests = server.requests();
+        assert_eq!(requests[5].header("cookie"), Some("theme=dark; session=abc"));
+        assert_eq!(requests[6].header("cookie"), None);
+    }
+
+    #[test]
+    fn test_build_request() {
+        let form = RequestBody::Form(crate::model::test::FormBody { form: HashMap::from([("q".to_string(), "a b".to_string())]) });
+        let request = build_request(
+            "post",
+            "https://example.com/search?page=1",
+            Some(&HashMap::from([("sort".to_string(), "new&old".to_string())])),
+            None,
+            Some(&form),
+            Bytes::new(),
+        )
+        .unwrap();
+        assert_eq!(request.method(), Method::POST);
+        assert_eq!(request.url().as_str(), "https://example.com/search?page=1&sort=new%26old");
+        assert_eq!(request.headers()[CONTENT_TYPE], "application/x-www-form-urlencoded");
+        assert_eq!(request.body().and_then(|body| body.as_bytes()), Some(&b"q=a+b"[..]));
 
-    tracing::info!("perform_test finished and dropped sender.");
-    Ok(())
-}
\ No newline at end of file
+        // A Content-Type given in the headers is kept
+        let headers = HashMap::from([("content-type".to_string(), "application/vnd.api+json".to_string())]);
+        let json = RequestBody::Json(json!({"id": 1}));
+        let request = build_request("PUT", "https://example.com/items/1", None, Some(&headers), Some(&json), Bytes::new()).unwrap();
+        assert_eq!(request.headers().get_all(CONTENT_TYPE).iter().count(), 1);
+        assert_eq!(request.headers()[CONTENT_TYPE], "application/vnd.api+json");
+        assert_eq!(request.url().as_str(), "https://example.com/items/1");
+
+        assert!(build_request("GET", "not a url", None, None, None, Bytes::new()).is_err());
+        assert!(build_request("BREW", "https://example.com", None, None, None, Bytes::new()).is_err());
+    }
+
+    #[test]
+    fn test_render_reuses_file_body() {
+        let template: RequestTemplate = serde_json::from_value(json!({
+            "method": "PUT",
+            "url": "https://example.com/users/{{id}}",
+            "body": { "type": "file", "path": "not-on-disk-{{id}}.json" }
+        }))
+        .unwrap();
+        let file_content = Some(Bytes::from_static(b"{\"name\": \"demo\"}"));
+        let prepared = PreparedRequest {
+            request: build_template_request(&template, file_content.clone()).unwrap(),
+            file_content,
+            think_time: None,
+            endpoint: None,
+            template,
+        };
+
+        // The file is not read again, so rendering works although it does not exist
+        let rendered = prepared.render(&HashMap::from([("id".to_string(), json!(7))])).unwrap();
+        assert_eq!(rendered.request.url().as_str(), "https://example.com/users/7");
+        assert_eq!(rendered.request.headers()[CONTENT_TYPE], "application/json");
+        let body = rendered.request.body().and_then(|body| body.as_bytes()).unwrap();
+        assert_eq!(body, b"{\"name\": \"demo\"}");
+    }
+}
@@ -0,0 +1,582 @@
+use anyhow::{Context, Result};
+use futures::stream::FuturesUnordered;
+use futures::{future, StreamExt};
+use rand::distributions::{Distribution, WeightedIndex};
+use rand::Rng;
+use reqwest::cookie::Jar;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tokio::time::Instant;
+
+use crate::http::client::{create_optimized_client, prepare_request, send_session_request, PreparedRequest};
+use crate::http::sitemap::load_sitemap;
+use crate::http::timing::TimedClient;
+use crate::model::failure::{ErrorClass, RequestFailure};
+use crate::model::metrics::EngineStats;
+use crate::model::test::api_test::extract_variables;
+use crate::model::test::load_test::feeder::{FeederExhausted, OnExhausted};
+use crate::model::test::stress_test::{stage_target, stages_peak};
+use crate::model::test::{
+    AdaptiveSample, ArrivalRate, FeederSet, RequestOutcome, RequestResult, RequestTemplate, SessionOptions, SitemapOrder, SuccessCriteria, TestConfig,
+    ThinkTime,
+};
+
+// Scheduling lag below this is never counted as a late iteration, whatever the rate
+const MIN_LATE_THRESHOLD: Duration = Duration::from_millis(10);
+
+// How often a low or ramping arrival rate is re-evaluated
+const RATE_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+// How often a virtual user above the current target checks whether it may run again
+const STAGE_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+// Rounding slack when one interval's worth of credit adds up to just under one iteration
+const CREDIT_EPSILON: f64 = 1e-9;
+
+// What every iteration sends: the whole sequence, or one request picked from a pool
+enum IterationPlan {
+    Sequence(Vec<PreparedRequest>),
+    Weighted(Vec<PreparedRequest>, WeightedIndex<f64>),
+    RoundRobin(Vec<PreparedRequest>, AtomicUsize),
+    Random(Vec<PreparedRequest>),
+}
+
+impl IterationPlan {
+    // Requests of the next iteration; not async, as thread_rng cannot be held across an await
+    fn next_requests(&self) -> &[PreparedRequest] {
+        match self {
+            IterationPlan::Sequence(sequence) => sequence,
+            IterationPlan::Weighted(pool, weights) => {
+                std::slice::from_ref(&pool[weights.sample(&mut rand::thread_rng())])
+            }
+            IterationPlan::RoundRobin(pool, next) => {
+                std::slice::from_ref(&pool[next.fetch_add(1, Ordering::Relaxed) % pool.len()])
+            }
+            IterationPlan::Random(pool) => {
+                std::slice::from_ref(&pool[rand::thread_rng().gen_range(0..pool.len())])
+            }
+        }
+    }
+}
+
+fn weighted_index(weights: impl IntoIterator<Item = f64>, source: &str) -> Result<WeightedIndex<f64>> {
+    WeightedIndex::new(weights).map_err(|e| anyhow::anyhow!("Invalid {} weights: {}", source, e))
+}
+
+// Prepare the scenario, the sitemap, the request sequence or the single target_url request, in that order
+async fn prepare_plan(config: &TestConfig) -> Result<IterationPlan> {
+    if let Some(scenario) = config.scenario.as_deref().filter(|scenario| !scenario.is_empty()) {
+        let weights = weighted_index(scenario.iter().map(|endpoint| endpoint.weight as f64), "scenario")?;
+        let mut endpoints = Vec::with_capacity(scenario.len());
+        for endpoint in scenario {
+            let mut prepared = prepare_request(&endpoint.request)
+                .await
+                .with_context(|| format!("Invalid scenario endpoint '{}'", endpoint.name))?;
+            prepared.endpoint = Some(Arc::from(endpoint.name.as_str()));
+            endpoints.push(prepared);
+        }
+        return Ok(IterationPlan::Weighted(endpoints, weights));
+    }
+
+    if let Some(source) = &config.sitemap {
+        let entries = load_sitemap(&create_optimized_client(), source).await?;
+        let mut pool = Vec::with_capacity(entries.len());
+        for entry in &entries {
+            let template = RequestTemplate { url: entry.url.clone(), ..config.target_request() };
+            pool.push(prepare_request(&template).await?);
+        }
+        return Ok(match source.order {
+            SitemapOrder::RoundRobin => IterationPlan::RoundRobin(pool, AtomicUsize::new(0)),
+            SitemapOrder::Random => IterationPlan::Random(pool),
+            SitemapOrder::Priority => {
+                // Priorities that are all 0 express no preference, so every URL gets the same weight
+                let uniform = entries.iter().all(|entry| entry.priority == 0.0);
+                let priorities = entries.iter().map(|entry| if uniform { 1.0 } else { entry.priority });
+                let weights = weighted_index(priorities, "sitemap priority")?;
+                IterationPlan::Weighted(pool, weights)
+            }
+        });
+    }
+
+    let templates = match config.requests.as_deref() {
+        Some(requests) if !requests.is_empty() => requests.to_vec(),
+        _ => vec![config.target_request()],
+    };
+    let mut sequence = Vec::with_capacity(templates.len());
+    for template in &templates {
+        sequence.push(prepare_request(template).await?);
+    }
+    Ok(IterationPlan::Sequence(sequence))
+}
+
+// State a virtual user carries from one iteration to the next
+struct VirtualUser {
+    id: usize,
+    jar: Option<Jar>,
+    // Own connection pool, with `connection_per_user`
+    client: Option<TimedClient>,
+    // Variables extracted from its responses
+    vars: HashMap<String, Value>,
+}
+
+// Counts a virtual user in `EngineStats::active_users` while alive
+struct ActiveUser<'a>(&'a AtomicU64);
+
+impl<'a> ActiveUser<'a> {
+    fn new(active_users: &'a AtomicU64) -> Self {
+        active_users.fetch_add(1, Ordering::Relaxed);
+        ActiveUser(active_users)
+    }
+}
+
+impl Drop for ActiveUser<'_> {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+// Requests completed since the adaptive controller last looked
+#[derive(Default)]
+struct AdaptiveWindow {
+    requests: AtomicU64,
+    errors: AtomicU64,
+    latency_us: AtomicU64,
+}
+
+impl AdaptiveWindow {
+    fn record(&self, result: &Result<RequestResult>) {
+        self.requests.fetch_add(1, Ordering::Relaxed);
+        match result {
+            Ok(result) if result.failure.is_none() => {
+                self.latency_us.fetch_add(result.duration.as_micros() as u64, Ordering::Relaxed);
+            }
+            _ => {
+                self.errors.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+    }
+
+    // Reset the window and return what it held; the average covers successful requests only
+    fn take(&self) -> AdaptiveSample {
+        let requests = self.requests.swap(0, Ordering::Relaxed);
+        let errors = self.errors.swap(0, Ordering::Relaxed);
+        let latency_us = self.latency_us.swap(0, Ordering::Relaxed);
+        let successes = requests.saturating_sub(errors);
+        AdaptiveSample {
+            requests,
+            errors,
+            average_response_time: if successes > 0 { latency_us as f64 / successes as f64 / 1000.0 } else { 0.0 },
+        }
+    }
+}
+
+// Everything an iteration needs, shared by the closed and open executors
+struct IterationRunner<'a> {
+    // Sends every request, timing its phases
+    transport: TimedClient,
+    session: SessionOptions,
+    plan: IterationPlan,
+    feeders: FeederSet,
+    // First feeder that ran out of rows with `on_exhausted` set to stop or error
+    exhausted: Mutex<Option<FeederExhausted>>,
+    think_time: Option<ThinkTime>,
+    pacing: Option<Duration>,
+    success: SuccessCriteria,
+    // Results of the current interval, with `adaptive`
+    adaptive_window: Option<AdaptiveWindow>,
+    sender: mpsc::Sender<RequestOutcome>,
+    is_finished: &'a AtomicBool,
+}
+
+impl IterationRunner<'_> {
+    fn new_user(&self, id: usize) -> VirtualUser {
+        VirtualUser {
+            id,
+            jar: self.session.cookies.then(Jar::default),
+            client: self.session.connection_per_user.then(|| self.transport.new_pool()),
+            vars: HashMap::new(),
+        }
+    }
+
+    // Run one iteration for a virtual user; false once the result receiver is gone
+    async fn run(&self, user: &mut VirtualUser, scheduled: Option<Instant>, pause_after_last: bool) -> bool {
+        let row = if self.feeders.is_empty() {
+            None
+        } else {
+            match self.feeders.next_vars(user.id) {
+                Ok(row) => Some(row),
+                Err(feeder_exhausted) => {
+                    self.is_finished.store(true, Ordering::SeqCst);
+                    self.exhausted.lock().unwrap_or_else(|e| e.into_inner()).get_or_insert(feeder_exhausted);
+                    return true;
+                }
+            }
+        };
+        let requests = self.plan.next_requests();
+        self.run_sequence(user, requests, row.as_ref(), scheduled, pause_after_last).await
+    }
+
+    // Send the requests in order, pausing for think time after each one (after the last only when
+    // asked); the pauses happen after the result is sent, so they never count as response time
+    async fn run_sequence(
+        &self,
+        user: &mut VirtualUser,
+        requests: &[PreparedRequest],
+        row: Option<&HashMap<String, Value>>,
+        scheduled: Option<Instant>,
+        pause_after_last: bool,
+    ) -> bool {
+        for (index, prepared) in requests.iter().enumerate() {
+            if self.is_finished.load(Ordering::SeqCst) {
+                break;
+            }
+            // Open-model latency counts from the scheduled start, so queueing delay is not hidden
+            let queued = scheduled.filter(|_| index == 0).map(|at| at.elapsed()).unwrap_or_default();
+            let result = self.send(user, prepared, row).await.map(|mut result| {
+                result.duration += queued;
+                result
+            });
+            if let Some(window) = &self.adaptive_window {
+                window.record(&result);
+            }
+            let outcome = RequestOutcome {
+                endpoint: prepared.endpoint.clone(),
+                result,
+            };
+            if self.sender.send(outcome).await.is_err() {
+                return false;
+            }
+
+            if index + 1 == requests.len() && !pause_after_last {
+                break;
+            }
+            let think_time = prepared
+                .think_time
+                .or_else(|| self.think_time.as_ref().map(|think_time| think_time.sample(&mut rand::thread_rng())));
+            if let Some(think_time) = think_time.filter(|pause| !pause.is_zero()) {
+                tokio::time::sleep(think_time).await;
+            }
+        }
+        true
+    }
+
+    // Send a prepared request as is, or rendered from its template with the feeder row and the
+    // user's variables, then store what its `extract` rules pick from the response; a rule that
+    // picks nothing fails the request like a response breaking the success policy
+    async fn send(
+        &self,
+        user: &mut VirtualUser,
+        prepared: &PreparedRequest,
+        row: Option<&HashMap<String, Value>>,
+    ) -> Result<RequestResult> {
+        let client = user.client.as_ref().unwrap_or(&self.transport);
+        let rebuilt = if row.is_some() || !user.vars.is_empty() {
+            let mut vars = row.cloned().unwrap_or_default();
+            vars.extend(user.vars.iter().map(|(name, value)| (name.clone(), value.clone())));
+            Some(prepared.render(&vars)?)
+        } else {
+            None
+        };
+        let request = rebuilt.as_ref().unwrap_or(prepared);
+
+        let rules = request.template.extract.as_deref().filter(|rules| !rules.is_empty());
+        let (mut result, response) =
+            send_session_request(client, request, user.jar.as_ref(), &self.success, rules.is_some()).await?;
+        if let (Some(rules), Some(response)) = (rules, response) {
+            let (extracted, errors) = extract_variables(rules, &response);
+            user.vars.extend(extracted);
+            if !errors.is_empty() {
+                result.failure.get_or_insert_with(|| RequestFailure {
+                    class: ErrorClass::AssertionFailed,
+                    message: errors.join("; "),
+                });
+            }
+        }
+        Ok(result)
+    }
+}
+
+// Closed model: each virtual user loops over iterations, starting the next one when the previous
+// one and its pacing interval are over. Virtual user `n` only runs while the target exceeds `n`,
+// which is `concurrent_users`, follows the stage profile or is set by the adaptive controller.
+async fn run_virtual_users(runner: &IterationRunner<'_>, config: &TestConfig, stats: &EngineStats) {
+    let stages = config.stages.as_deref();
+    let adaptive = config.adaptive.as_ref();
+    let peak = match (stages, adaptive) {
+        (Some(stages), _) => stages_peak(stages),
+        (None, Some(adaptive)) => adaptive.max_users(config.concurrent_users),
+        (None, None) => config.concurrent_users,
+    } as usize;
+    if let Some(adaptive) = adaptive {
+        stats.set_target_load(adaptive.initial_users() as f64);
+    }
+    let start = stats.mark_started();
+    let iterations = &AtomicUsize::new(0);
+
+    let users = (0..peak).map(|id| async move {
+        let mut user = runner.new_user(id);
+        let mut active = None;
+        while !runner.is_finished.load(Ordering::SeqCst) {
+            let target = match (stages, adaptive) {
+                (Some(stages), _) => stage_target(stages, start.elapsed()),
+                (None, Some(_)) => Some(stats.target_load()),
+                (None, None) => Some(config.concurrent_users as f64),
+            };
+            let Some(target) = target else {
+                break;
+            };
+            if adaptive.is_none() {
+                stats.set_target_load(target);
+            }
+            if id as f64 >= target {
+                active = None;
+                tokio::time::sleep(STAGE_POLL_INTERVAL).await;
+                continue;
+            }
+            active.get_or_insert_with(|| ActiveUser::new(&stats.active_users));
+
+            if config.num_requests > 0 && iterations.fetch_add(1, Ordering::Relaxed) >= config.num_requests as usize {
+                break;
+            }
+            let started = Instant::now();
+            if !runner.run(&mut user, None, true).await {
+                break;
+            }
+            if let Some(pacing) = runner.pacing {
+                tokio::time::sleep_until(started + pacing).await;
+            }
+        }
+    });
+
+    // Move the target once per interval, from the results the interval completed
+    let controller = async {
+        let Some((adaptive, window)) = adaptive.zip(runner.adaptive_window.as_ref()) else {
+            return future::pending().await;
+        };
+        let interval = Duration::from_millis(adaptive.interval_ms());
+        loop {
+            tokio::time::sleep(interval).await;
+            let current = stats.target_load() as u32;
+            let next = adaptive.next_users(current, &window.take(), peak as u32);
+            if next != current {
+                tracing::debug!("Adaptive concurrency moved from {} to {} virtual users", current, next);
+            }
+            stats.set_target_load(next as f64);
+        }
+    };
+
+    tokio::select! {
+        _ = future::join_all(users) => {}
+        _ = controller => {}
+    }
+}
+
+// One interval of `rate` ahead, or sooner while the rate is too low to start an iteration each
+// step; rates so low that the interval overflows a Duration poll too
+fn schedule_step(rate: f64) -> Duration {
+    if rate > 0.0 {
+        Duration::try_from_secs_f64(1.0 / rate).map_or(RATE_POLL_INTERVAL, |step| step.min(RATE_POLL_INTERVAL))
+    } else {
+        RATE_POLL_INTERVAL
+    }
+}
+
+// Open model: iterations start on schedule, however slowly the target responds. The rate comes
+// from the stage profile when there is one. Each iteration borrows an idle virtual user, or a new
+// one when all are busy.
+async fn run_arrival_rate(
+    runner: &IterationRunner<'_>,
+    config: &TestConfig,
+    arrival_rate: &ArrivalRate,
+    stats: &EngineStats,
+) {
+    let rate_at = |elapsed: Duration| match config.stages.as_deref() {
+        Some(stages) => stage_target(stages, elapsed),
+        None => Some(arrival_rate.rate),
+    };
+    let max_in_flight = arrival_rate.max_in_flight.unwrap_or(config.concurrent_users).max(1) as usize;
+    let idle_users = &Mutex::new(Vec::<VirtualUser>::new());
+    let mut user_count = 0usize;
+    let start = stats.mark_started();
+    let mut scheduled = start;
+    // Iterations owed so far; the first one starts immediately
+    let mut credit = 1.0;
+    let mut in_flight = FuturesUnordered::new();
+    let mut iteration = 0usize;
+
+    loop {
+        if runner.is_finished.load(Ordering::SeqCst)
+            || (config.num_requests > 0 && iteration >= config.num_requests as usize)
+        {
+            break;
+        }
+
+        tokio::select! {
+            biased;
+            // Completed iterations are reaped here; one that lost the receiver ends the test
+            Some(false) = in_flight.next(), if !in_flight.is_empty() => break,
+            _ = tokio::time::sleep_until(scheduled) => {
+                let Some(rate) = rate_at(scheduled - start) else {
+                    break;
+                };
+                stats.set_target_load(rate);
+                let step = schedule_step(rate);
+
+                if credit >= 1.0 - CREDIT_EPSILON {
+                    credit -= 1.0;
+                    if in_flight.len() >= max_in_flight {
+                        stats.dropped_iterations.fetch_add(1, Ordering::Relaxed);
+                    } else {
+                        if scheduled.elapsed() > step.max(MIN_LATE_THRESHOLD) {
+                            stats.late_iterations.fetch_add(1, Ordering::Relaxed);
+                        }
+                        let idle = idle_users.lock().unwrap_or_else(|e| e.into_inner()).pop();
+                        let mut user = idle.unwrap_or_else(|| {
+                            user_count += 1;
+                            runner.new_user(user_count - 1)
+                        });
+                        let iteration_start = scheduled;
+                        in_flight.push(async move {
+                            let active = ActiveUser::new(&stats.active_users);
+                            let send_success = runner.run(&mut user, Some(iteration_start), false).await;
+                            drop(active);
+                            idle_users.lock().unwrap_or_else(|e| e.into_inner()).push(user);
+                            send_success
+                        });
+                    }
+                    iteration += 1;
+                }
+
+                credit += rate * step.as_secs_f64();
+                scheduled += step;
+            }
+        }
+    }
+
+    while in_flight.next().await.is_some() {}
+}
+
+/// Run a load/stress test with virtual user loops or the arrival-rate executor
+pub(crate) async fn perform_test(
+    config: &TestConfig,
+    result_sender: mpsc::Sender<RequestOutcome>,
+    is_finished: Arc<AtomicBool>,
+    stats: Arc<EngineStats>,
+) -> Result<()> {
+    let session = config.session.clone().unwrap_or_default();
+    let runner = IterationRunner {
+        transport: TimedClient::new()?,
+        session,
+        plan: prepare_plan(config).await?,
+        feeders: FeederSet::load(config.feeders.as_deref().unwrap_or_default()).await?,
+        exhausted: Mutex::new(None),
+        think_time: config.think_time.clone(),
+        pacing: config.pacing_ms.map(Duration::from_millis),
+        success: config.success.as_ref().map(SuccessCriteria::new).transpose()?.unwrap_or_default(),
+        adaptive_window: config.adaptive.as_ref().map(|_| AdaptiveWindow::default()),
+        sender: result_sender,
+        is_finished: &is_finished,
+    };
+
+    match &config.arrival_rate {
+        Some(arrival_rate) => run_arrival_rate(&runner, config, arrival_rate, &stats).await,
+        None => run_virtual_users(&runner, config, &stats).await,
+    }
+
+    if let Some(feeder_exhausted) = runner.exhausted.lock().unwrap_or_else(|e| e.into_inner()).take() {
+        if feeder_exhausted.on_exhausted == OnExhausted::Error {
+            anyhow::bail!("Data feeder {} ran out of rows", feeder_exhausted.file);
+        }
+        tracing::info!("Data feeder {} ran out of rows, stopping the test", feeder_exhausted.file);
+    }
+
+    tracing::info!("perform_test finished and dropped sender.");
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::http::test_server::{ReceivedRequest, Reply, TestServer};
+    use serde_json::json;
+
+    // Users log in once per iteration; the server keeps the user id they got first in a cookie
+    // and hands out a token for it, which the next request sends back
+    async fn run_sessions(connection_per_user: bool) -> Vec<ReceivedRequest> {
+        let next_user = AtomicUsize::new(0);
+        let server = TestServer::start(move |request| match request.path.as_str() {
+            "/login" => {
+                let cookie = request.header("cookie").and_then(|cookie| cookie.strip_prefix("uid="));
+                let uid = cookie.map_or_else(|| next_user.fetch_add(1, Ordering::Relaxed).to_string(), str::to_string);
+                Reply::ok(&json!({ "token": format!("t{}", uid) }).to_string()).header("Set-Cookie", &format!("uid={}", uid))
+            }
+            _ => Reply::ok("profile"),
+        })
+        .await;
+        let config: TestConfig = serde_json::from_value(json!({
+            "target_url": server.url,
+            "concurrent_users": 2,
+            "duration_secs": 0,
+            "num_requests": 6,
+            "requests": [
+                { "method": "POST", "url": format!("{}/login", server.url), "extract": [{ "name": "token", "json_path": "$.token" }] },
+                { "url": format!("{}/profile", server.url), "headers": { "Authorization": "Bearer {{token}}" } }
+            ],
+            "session": { "cookies": true, "connection_per_user": connection_per_user }
+        }))
+        .unwrap();
+
+        let (sender, mut receiver) = mpsc::channel::<RequestOutcome>(100);
+        let drain = tokio::spawn(async move {
+            while let Some(outcome) = receiver.recv().await {
+                let result = outcome.result.unwrap();
+                assert_eq!(result.failure, None);
+            }
+        });
+        let stats = Arc::new(EngineStats::default());
+        perform_test(&config, sender, Arc::new(AtomicBool::new(false)), stats).await.unwrap();
+        drain.await.unwrap();
+        server.requests()
+    }
+
+    #[test]
+    fn test_schedule_step() {
+        assert_eq!(schedule_step(100.0), Duration::from_millis(10));
+        assert_eq!(schedule_step(0.5), RATE_POLL_INTERVAL);
+        assert_eq!(schedule_step(0.0), RATE_POLL_INTERVAL);
+        assert_eq!(schedule_step(1e-300), RATE_POLL_INTERVAL);
+        assert_eq!(schedule_step(f64::MIN_POSITIVE / 4.0), RATE_POLL_INTERVAL);
+    }
+
+    #[tokio::test]
+    async fn test_session_cookies_and_variables_per_user() {
+        let requests = run_sessions(false).await;
+        assert_eq!(requests.len(), 12);
+        let profiles: Vec<_> = requests.iter().filter(|request| request.path == "/profile").collect();
+        for profile in &profiles {
+            let uid = profile.header("cookie").and_then(|cookie| cookie.strip_prefix("uid=")).unwrap();
+            assert_eq!(profile.header("authorization"), Some(format!("Bearer t{}", uid).as_str()));
+        }
+        let mut uids: Vec<_> = profiles.iter().map(|profile| profile.header("cookie")).collect();
+        uids.sort();
+        uids.dedup();
+        assert_eq!(uids.len(), 2, "each virtual user keeps its cookie");
+    }
+
+    #[tokio::test]
+    async fn test_connection_per_user() {
+        let requests = run_sessions(true).await;
+        let mut users_by_connection: HashMap<usize, Vec<&str>> = HashMap::new();
+        for request in requests.iter().filter(|request| request.path == "/profile") {
+            let users = users_by_connection.entry(request.connection).or_default();
+            users.push(request.header("cookie").unwrap());
+            users.dedup();
+        }
+        assert_eq!(users_by_connection.len(), 2);
+        assert!(users_by_connection.values().all(|users| users.len() == 1));
+    }
+}
@@ -0,0 +1,109 @@
+use std::error::Error as StdError;
+use std::io::ErrorKind;
+
+use crate::model::failure::ErrorClass;
+
+// Context `http::client` puts on failures while reading a response body
+pub(crate) const BODY_READ_CONTEXT: &str = "Failed to read response body";
+
+/// Classify a transport error returned instead of a response
+pub fn classify_error(error: &anyhow::Error) -> ErrorClass {
+    let chain: Vec<&(dyn StdError + 'static)> = error.chain().collect();
+
+    if let Some(reqwest_error) = chain.iter().find_map(|cause| cause.downcast_ref::<reqwest::Error>()) {
+        if reqwest_error.is_timeout() {
+            return if reqwest_error.is_connect() { ErrorClass::ConnectTimeout } else { ErrorClass::RequestTimeout };
+        }
+    }
+    if chain.iter().any(|cause| cause.is::<tokio::time::error::Elapsed>()) {
+        return ErrorClass::RequestTimeout;
+    }
+
+    let io_kind = chain.iter().find_map(|cause| cause.downcast_ref::<std::io::Error>()).map(|io| io.kind());
+    match io_kind {
+        Some(ErrorKind::ConnectionRefused) => return ErrorClass::ConnectRefused,
+        Some(ErrorKind::TimedOut) => return ErrorClass::ConnectTimeout,
+        Some(ErrorKind::ConnectionReset | ErrorKind::ConnectionAborted | ErrorKind::BrokenPipe) => {
+            return ErrorClass::ConnectionReset;
+        }
+        _ => {}
+    }
+
+    // DNS, TLS and early-close errors only show up in the messages of hyper and the TLS backend.
+    // Context layers and reqwest's own message name the URL, so only the causes below them count.
+    let below = chain
+        .iter()
+        .rposition(|cause| cause.is::<reqwest::Error>())
+        .map_or(1, |index| index + 1);
+    let messages: Vec<String> = chain.iter().skip(below).map(|cause| cause.to_string().to_ascii_lowercase()).collect();
+    let mentions = |needles: &[&str]| messages.iter().any(|message| needles.iter().any(|needle| message.contains(needle)));
+    let body_error = chain.iter().any(|cause| cause.downcast_ref::<reqwest::Error>().is_some_and(|e| e.is_body() || e.is_decode()))
+        || chain.iter().any(|cause| cause.to_string().starts_with(BODY_READ_CONTEXT));
+
+    if mentions(&["dns error", "failed to lookup address", "name or service not known", "no such host"]) {
+        ErrorClass::Dns
+    } else if mentions(&["certificate", "ssl", "tls", "handshake"]) {
+        ErrorClass::Tls
+    } else if mentions(&["connection reset", "connection closed before message completed", "broken pipe"]) {
+        ErrorClass::ConnectionReset
+    } else if body_error {
+        ErrorClass::BodyRead
+    } else {
+        ErrorClass::Other
+    }
+}
+
+/// Short message for a transport error: the causes below its outermost context, which names the
+/// request URL, so that the same failure on different URLs shares one message
+pub fn error_message(error: &anyhow::Error) -> String {
+    let mut message = String::new();
+    for cause in error.chain().skip(1) {
+        let cause = cause.to_string();
+        // hyper repeats its source at the end of its own message
+        if message.ends_with(&cause) {
+            continue;
+        }
+        if !message.is_empty() {
+            message.push_str(": ");
+        }
+        message.push_str(&cause);
+    }
+    if message.is_empty() { error.to_string() } else { message }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::Context;
+    use std::io;
+
+    fn failed(error: io::Error, context: &'static str) -> anyhow::Error {
+        Err::<(), _>(error).context(context).unwrap_err()
+    }
+
+    #[test]
+    fn test_classify_error() {
+        let refused = failed(io::Error::from(ErrorKind::ConnectionRefused), "Failed to send GET request to https://tls.example/");
+        assert_eq!(classify_error(&refused), ErrorClass::ConnectRefused);
+        assert_eq!(error_message(&refused), "connection refused");
+        let other_url = failed(io::Error::from(ErrorKind::ConnectionRefused), "Failed to send GET request to https://other.example/");
+        assert_eq!(error_message(&other_url), error_message(&refused));
+
+        let reset = failed(io::Error::from(ErrorKind::ConnectionReset), "Failed to send GET request");
+        assert_eq!(classify_error(&reset), ErrorClass::ConnectionReset);
+
+        let dns = failed(io::Error::other("dns error: failed to lookup address information"), "Failed to send");
+        assert_eq!(classify_error(&dns), ErrorClass::Dns);
+
+        let tls = failed(io::Error::other("error:0A000086:SSL routines::certificate verify failed"), "Failed to send");
+        assert_eq!(classify_error(&tls), ErrorClass::Tls);
+
+        let body = failed(io::Error::other("unexpected end of file"), "Failed to read response body");
+        assert_eq!(classify_error(&body), ErrorClass::BodyRead);
+        let body = body.context("Failed to send GET request to https://tls.example/");
+        assert_eq!(error_message(&body), "Failed to read response body: unexpected end of file");
+
+        let other = failed(io::Error::other("invalid header"), "Failed to send GET request to https://ssl.example/");
+        assert_eq!(classify_error(&other), ErrorClass::Other);
+    }
+}
@@ -1,6 +1,14 @@
 pub mod client;
+pub mod curl;
+pub mod engine;
+pub mod error_class;
+pub mod sitemap;
+pub mod timing;
 mod request;
+#[cfg(test)]
+pub(crate) mod test_server;
 
 // Re-export client functions
-pub use client::{send_request, send_api_request, string_to_method};
+pub use client::{send_api_request, string_to_method};
+pub use curl::{parse_curl, render_curl, render_template_curl};
 pub use request::*;
\ No newline at end of file
@@ -19,7 +19,19 @@ pub fn convert_legacy_config(config: &LegacyTestConfig) -> TestConfig {
         target_url: config.urls.first().unwrap_or(&String::new()).clone(),
         concurrent_users: config.concurrency,
         duration_secs: config.duration.unwrap_or(0) as u32,
-        num_requests: config.total_requests.unwrap_or(0) as u32,
+        num_requests: config.total_requests.unwrap_or(0),
+        request: Default::default(),
+        requests: None,
+        scenario: None,
+        sitemap: None,
+        feeders: None,
+        arrival_rate: None,
+        stages: None,
+        think_time: None,
+        pacing_ms: None,
+        session: None,
+        success: None,
+        adaptive: None,
     }
 }
 
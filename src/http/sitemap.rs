@@ -0,0 +1,133 @@
+use anyhow::{Context, Result};
+use reqwest::Client;
+use std::collections::{HashSet, VecDeque};
+
+use crate::model::utils::fixture::read_fixture_to_string;
+use crate::model::test::load_test::sitemap::{
+    parse_sitemap, Sitemap, SitemapEntry, SitemapFilter, SitemapSource, DEFAULT_MAX_URLS,
+};
+
+// Sitemap indexes nested deeper than this are not followed
+const MAX_SITEMAP_DEPTH: usize = 3;
+
+// Read a sitemap from a URL or, when `allow_file` is set, from a file in the fixtures directory.
+// Only the sitemap a test names may be a file; locations listed by a fetched sitemap index must
+// be URLs, so a remote index cannot make the server read its files.
+async fn fetch_sitemap(client: &Client, location: &str, allow_file: bool) -> Result<String> {
+    if location.starts_with("http://") || location.starts_with("https://") {
+        client.get(location)
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .with_context(|| format!("Failed to fetch sitemap {}", location))?
+            .text()
+            .await
+            .with_context(|| format!("Failed to read sitemap {}", location))
+    } else if !allow_file {
+        anyhow::bail!("Nested sitemap {} is not an http(s) URL", location)
+    } else {
+        read_fixture_to_string(location)
+            .await
+            .with_context(|| format!("Failed to read sitemap file {}", location))
+    }
+}
+
+/// Load the URLs of a sitemap, following sitemap indexes and applying the source's filters and cap
+pub async fn load_sitemap(client: &Client, source: &SitemapSource) -> Result<Vec<SitemapEntry>> {
+    let filter = SitemapFilter::new(source)?;
+    let max_urls = source.max_urls.unwrap_or(DEFAULT_MAX_URLS);
+
+    let mut pending = VecDeque::from([(source.location.clone(), 0usize)]);
+    let mut visited: HashSet<String> = HashSet::new();
+    let mut entries: Vec<SitemapEntry> = Vec::new();
+
+    while let Some((location, depth)) = pending.pop_front() {
+        if entries.len() >= max_urls {
+            break;
+        }
+        if !visited.insert(location.clone()) {
+            continue;
+        }
+
+        let sitemap = async {
+            let content = fetch_sitemap(client, &location, depth == 0).await?;
+            parse_sitemap(&content).with_context(|| format!("Failed to parse sitemap {}", location))
+        };
+        let sitemap = match sitemap.await {
+            Ok(sitemap) => sitemap,
+            // A broken nested sitemap only loses its own URLs
+            Err(e) if depth > 0 => {
+                tracing::warn!("Skipping sitemap {}: {:#}", location, e);
+                continue;
+            }
+            Err(e) => return Err(e),
+        };
+        match sitemap {
+            Sitemap::Urls(urls) => {
+                let remaining = max_urls - entries.len();
+                entries.extend(urls.into_iter().filter(|entry| filter.matches(&entry.url)).take(remaining));
+            }
+            Sitemap::Index(nested) if depth < MAX_SITEMAP_DEPTH => {
+                pending.extend(nested.into_iter().map(|nested| (nested, depth + 1)));
+            }
+            Sitemap::Index(_) => {
+                tracing::warn!("Skipping sitemap index {} nested deeper than {} levels", location, MAX_SITEMAP_DEPTH);
+            }
+        }
+    }
+
+    if entries.is_empty() {
+        anyhow::bail!("Sitemap {} contains no URLs matching the filters", source.location);
+    }
+    tracing::info!("Loaded {} URLs from sitemap {}", entries.len(), source.location);
+    Ok(entries)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::http::test_server::{Reply, TestServer};
+    use crate::model::test::SitemapOrder;
+
+    #[tokio::test]
+    async fn test_load_sitemap_skips_broken_nested_sitemaps() {
+        let server = TestServer::start(|request| match request.path.as_str() {
+            "/index.xml" => {
+                let host = request.header("host").unwrap_or_default();
+                Reply::ok(&format!(
+                    r#"<sitemapindex xmlns="http://www.sitemaps.org/schemas/sitemap/0.9">
+                        <sitemap><loc>http://{host}/missing.xml</loc></sitemap>
+                        <sitemap><loc>http://{host}/pages.xml</loc></sitemap>
+                    </sitemapindex>"#
+                ))
+            }
+            "/pages.xml" => Reply::ok(
+                r#"<urlset xmlns="http://www.sitemaps.org/schemas/sitemap/0.9">
+                    <url><loc>https://example.com/a</loc></url>
+                </urlset>"#,
+            ),
+            _ => Reply::status(404, "not found"),
+        })
+        .await;
+        let source = |path: &str| SitemapSource {
+            location: format!("{}{}", server.url, path),
+            order: SitemapOrder::RoundRobin,
+            max_urls: None,
+            path_prefix: None,
+            pattern: None,
+        };
+
+        let entries = load_sitemap(&Client::new(), &source("/index.xml")).await.unwrap();
+        assert_eq!(entries.iter().map(|entry| entry.url.as_str()).collect::<Vec<_>>(), ["https://example.com/a"]);
+        assert!(load_sitemap(&Client::new(), &source("/missing.xml")).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_nested_sitemaps_must_be_urls() {
+        let error = fetch_sitemap(&Client::new(), "users.csv", false).await.unwrap_err();
+        assert_eq!(error.to_string(), "Nested sitemap users.csv is not an http(s) URL");
+        // The sitemap a test names may still be a fixture file
+        let error = fetch_sitemap(&Client::new(), "missing-sitemap.xml", true).await.unwrap_err();
+        assert_eq!(error.to_string(), "Failed to read sitemap file missing-sitemap.xml");
+    }
+}
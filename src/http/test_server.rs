@@ -0,0 +1,141 @@
+//! Minimal keep-alive HTTP/1.1 server for tests of the load test client
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+use tokio::net::TcpListener;
+
+/// A request as the server received it
+#[derive(Debug, Clone)]
+pub struct ReceivedRequest {
+    /// Index of the connection the request came in on, counted from 0
+    pub connection: usize,
+    pub method: String,
+    pub path: String,
+    pub headers: Vec<(String, String)>,
+    pub body: Vec<u8>,
+}
+
+impl ReceivedRequest {
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+/// The response to send back
+pub struct Reply {
+    status: u16,
+    headers: Vec<(String, String)>,
+    body: String,
+    stall_body: bool,
+}
+
+impl Reply {
+    pub fn ok(body: &str) -> Self {
+        Reply { status: 200, headers: Vec::new(), body: body.to_string(), stall_body: false }
+    }
+
+    pub fn status(status: u16, body: &str) -> Self {
+        Reply { status, ..Reply::ok(body) }
+    }
+
+    pub fn redirect(status: u16, location: &str) -> Self {
+        Reply::status(status, "").header("Location", location)
+    }
+
+    pub fn header(mut self, name: &str, value: &str) -> Self {
+        self.headers.push((name.to_string(), value.to_string()));
+        self
+    }
+
+    /// Send the headers, then hold back the body
+    pub fn stall_body(mut self) -> Self {
+        self.stall_body = true;
+        self
+    }
+}
+
+type Handler = dyn Fn(&ReceivedRequest) -> Reply + Send + Sync;
+
+pub struct TestServer {
+    /// Base URL, without a trailing slash
+    pub url: String,
+    requests: Arc<Mutex<Vec<ReceivedRequest>>>,
+}
+
+impl TestServer {
+    pub async fn start(handler: impl Fn(&ReceivedRequest) -> Reply + Send + Sync + 'static) -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let handler: Arc<Handler> = Arc::new(handler);
+        let connections = AtomicUsize::new(0);
+
+        let received = Arc::clone(&requests);
+        tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                let connection = connections.fetch_add(1, Ordering::Relaxed);
+                let (handler, received) = (Arc::clone(&handler), Arc::clone(&received));
+                tokio::spawn(async move {
+                    let (reader, mut writer) = stream.into_split();
+                    let mut reader = BufReader::new(reader);
+                    while let Some(request) = read_request(&mut reader, connection).await {
+                        let reply = handler(&request);
+                        received.lock().unwrap().push(request);
+                        if write_reply(&mut writer, reply).await.is_err() {
+                            break;
+                        }
+                    }
+                });
+            }
+        });
+        TestServer { url, requests }
+    }
+
+    /// Every request received so far, in order
+    pub fn requests(&self) -> Vec<ReceivedRequest> {
+        self.requests.lock().unwrap().clone()
+    }
+}
+
+async fn read_request(reader: &mut BufReader<tokio::net::tcp::OwnedReadHalf>, connection: usize) -> Option<ReceivedRequest> {
+    let mut line = String::new();
+    reader.read_line(&mut line).await.ok().filter(|read| *read > 0)?;
+    let mut parts = line.split_whitespace();
+    let (method, path) = (parts.next()?.to_string(), parts.next()?.to_string());
+
+    let mut headers = Vec::new();
+    loop {
+        let mut line = String::new();
+        reader.read_line(&mut line).await.ok().filter(|read| *read > 0)?;
+        let line = line.trim_end();
+        if line.is_empty() {
+            break;
+        }
+        let (name, value) = line.split_once(':')?;
+        headers.push((name.trim().to_string(), value.trim().to_string()));
+    }
+
+    let mut request = ReceivedRequest { connection, method, path, headers, body: Vec::new() };
+    let length = request.header("content-length").and_then(|length| length.parse().ok()).unwrap_or(0);
+    request.body = vec![0; length];
+    reader.read_exact(&mut request.body).await.ok()?;
+    Some(request)
+}
+
+async fn write_reply(writer: &mut tokio::net::tcp::OwnedWriteHalf, reply: Reply) -> std::io::Result<()> {
+    let mut head = format!("HTTP/1.1 {} Test\r\nContent-Length: {}\r\n", reply.status, reply.body.len());
+    for (name, value) in &reply.headers {
+        head.push_str(&format!("{}: {}\r\n", name, value));
+    }
+    head.push_str("\r\n");
+    writer.write_all(head.as_bytes()).await?;
+    if reply.stall_body {
+        tokio::time::sleep(Duration::from_secs(3600)).await;
+    }
+    writer.write_all(reply.body.as_bytes()).await
+}
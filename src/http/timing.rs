@@ -0,0 +1,337 @@
+use anyhow::{Context as _, Result};
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+use hyper_014::body::{Body, Bytes};
+use hyper_014::client::connect::{Connected, Connection};
+use hyper_014::service::Service;
+use hyper_014::{Client, Uri};
+use reqwest::header::{HeaderMap, HeaderValue, ACCEPT, AUTHORIZATION};
+use reqwest::{Request, StatusCode};
+use std::future::Future;
+use std::io::{self, ErrorKind};
+use std::net::{IpAddr, SocketAddr};
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use socket2::{SockRef, TcpKeepalive};
+use std::time::{Duration, Instant};
+use tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
+use tokio::net::TcpStream;
+use tokio_native_tls::{native_tls, TlsConnector, TlsStream};
+
+use crate::http::error_class::BODY_READ_CONTEXT;
+use crate::model::phase::PhaseTimings;
+
+// Same limits as `create_optimized_client`; the connect timeout includes DNS resolution
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+const MAX_IDLE_PER_HOST: usize = 10;
+const TCP_KEEPALIVE: Duration = Duration::from_secs(60);
+
+/// HTTP/1 client for load/stress requests that times every phase of each request.
+///
+/// It sends a prepared reqwest `Request` as is: redirects and cookies are left to the caller, and
+/// `HTTP_PROXY`/`HTTPS_PROXY` are not honored.
+#[derive(Clone)]
+pub struct TimedClient {
+    client: Client<TimedConnector, Body>,
+    connector: TimedConnector,
+    request_timeout: Duration,
+}
+
+/// A fully read response and the time each phase took
+#[derive(Debug)]
+pub struct TimedResponse {
+    pub status: StatusCode,
+    pub headers: HeaderMap,
+    pub body: Bytes,
+    pub phases: PhaseTimings,
+}
+
+impl TimedClient {
+    pub fn new() -> Result<Self> {
+        let tls = native_tls::TlsConnector::new().context("Failed to create TLS connector")?;
+        Ok(Self::with_connector(TimedConnector { tls: TlsConnector::from(tls) }))
+    }
+
+    /// A client with the same settings and a connection pool of its own
+    pub fn new_pool(&self) -> Self {
+        Self::with_connector(self.connector.clone())
+    }
+
+    fn with_connector(connector: TimedConnector) -> Self {
+        let client = Client::builder()
+            .pool_max_idle_per_host(MAX_IDLE_PER_HOST)
+            .build(connector.clone());
+        TimedClient { client, connector, request_timeout: REQUEST_TIMEOUT }
+    }
+
+    /// Send `request` and read the whole response body
+    pub async fn execute(&self, request: &Request) -> Result<TimedResponse> {
+        let deadline = tokio::time::Instant::now() + self.request_timeout;
+        let body = match request.body() {
+            Some(body) => body.as_bytes().context("Streaming request bodies are not supported")?.to_vec(),
+            None => Vec::new(),
+        };
+        // URL credentials become basic auth, as reqwest does when building a request
+        let mut url = request.url().clone();
+        let credentials = (!url.username().is_empty() || url.password().is_some())
+            .then(|| format!("{}:{}", url.username(), url.password().unwrap_or_default()));
+        let _ = url.set_username("");
+        let _ = url.set_password(None);
+
+        let mut builder = hyper_014::Request::builder()
+            .method(request.method().clone())
+            .uri(url.as_str());
+        if let Some(headers) = builder.headers_mut() {
+            *headers = request.headers().clone();
+            headers.entry(ACCEPT).or_insert(HeaderValue::from_static("*/*"));
+            if let Some(credentials) = credentials {
+                let value = HeaderValue::from_str(&format!("Basic {}", STANDARD.encode(credentials)))?;
+                headers.entry(AUTHORIZATION).or_insert(value);
+            }
+        }
+        let hyper_request = builder.body(Body::from(body))?;
+
+        let sent = Instant::now();
+        let response = tokio::time::timeout_at(deadline, self.client.request(hyper_request)).await??;
+        let headers_received = Instant::now();
+
+        // A connection is charged to the first response it delivers, and only if it was opened
+        // while this request waited for one: an idle pooled connection was paid for earlier
+        let connection = response
+            .extensions()
+            .get::<NewConnection>()
+            .filter(|connection| connection.0.established >= sent)
+            .filter(|connection| !connection.0.claimed.swap(true, Ordering::Relaxed))
+            .map(|connection| Arc::clone(&connection.0));
+        let connection = connection.as_deref();
+        let setup = connection.map_or(Duration::ZERO, |connection| connection.setup_time());
+
+        let (parts, body) = response.into_parts();
+        let body = tokio::time::timeout_at(deadline, hyper_014::body::to_bytes(body))
+            .await
+            .map_err(anyhow::Error::from)
+            .and_then(|body| body.map_err(anyhow::Error::from))
+            .context(BODY_READ_CONTEXT)?;
+
+        Ok(TimedResponse {
+            status: parts.status,
+            headers: parts.headers,
+            body,
+            phases: PhaseTimings {
+                dns: connection.and_then(|connection| connection.dns),
+                connect: connection.map(|connection| connection.connect),
+                tls: connection.and_then(|connection| connection.tls),
+                ttfb: headers_received.duration_since(sent).saturating_sub(setup),
+                download: headers_received.elapsed(),
+            },
+        })
+    }
+}
+
+// Connection setup phases, attached by hyper to every response sent over the connection
+#[derive(Debug)]
+struct ConnectionPhases {
+    dns: Option<Duration>,
+    connect: Duration,
+    tls: Option<Duration>,
+    // When connection setup started, so a connection opened before a request is never its own
+    established: Instant,
+    // Set once a response has reported these phases
+    claimed: AtomicBool,
+}
+
+impl ConnectionPhases {
+    fn setup_time(&self) -> Duration {
+        self.dns.unwrap_or_default() + self.connect + self.tls.unwrap_or_default()
+    }
+}
+
+#[derive(Debug, Clone)]
+struct NewConnection(Arc<ConnectionPhases>);
+
+// Resolves, connects and, for https, runs the TLS handshake, timing each step
+#[derive(Clone)]
+struct TimedConnector {
+    tls: TlsConnector,
+}
+
+impl Service<Uri> for TimedConnector {
+    type Response = TimedStream;
+    type Error = io::Error;
+    type Future = Pin<Box<dyn Future<Output = io::Result<TimedStream>> + Send>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, uri: Uri) -> Self::Future {
+        let tls = self.tls.clone();
+        Box::pin(async move {
+            tokio::time::timeout(CONNECT_TIMEOUT, connect(uri, tls))
+                .await
+                .map_err(|_| io::Error::new(ErrorKind::TimedOut, "connect timed out"))?
+        })
+    }
+}
+
+async fn connect(uri: Uri, tls: TlsConnector) -> io::Result<TimedStream> {
+    let host = uri
+        .host()
+        .map(|host| host.trim_start_matches('[').trim_end_matches(']').to_string())
+        .ok_or_else(|| io::Error::new(ErrorKind::InvalidInput, "URL has no host"))?;
+    let https = uri.scheme_str() == Some("https");
+    let port = uri.port_u16().unwrap_or(if https { 443 } else { 80 });
+
+    let started = Instant::now();
+    let (addresses, dns) = match host.parse::<IpAddr>() {
+        Ok(ip) => (vec![SocketAddr::new(ip, port)], None),
+        Err(_) => {
+            let addresses: Vec<SocketAddr> = tokio::net::lookup_host((host.as_str(), port)).await?.collect();
+            (addresses, Some(started.elapsed()))
+        }
+    };
+
+    let connect_started = Instant::now();
+    let mut last_error = io::Error::new(ErrorKind::NotFound, format!("dns error: no addresses found for {}", host));
+    let mut tcp = None;
+    for address in addresses {
+        match TcpStream::connect(address).await {
+            Ok(stream) => {
+                tcp = Some(stream);
+                break;
+            }
+            Err(e) => last_error = e,
+        }
+    }
+    let tcp = tcp.ok_or(last_error)?;
+    tcp.set_nodelay(true)?;
+    SockRef::from(&tcp).set_tcp_keepalive(&TcpKeepalive::new().with_time(TCP_KEEPALIVE))?;
+    let connect = connect_started.elapsed();
+
+    let (stream, tls) = if https {
+        let tls_started = Instant::now();
+        let stream = tls.connect(&host, tcp).await.map_err(io::Error::other)?;
+        (Stream::Tls(Box::new(stream)), Some(tls_started.elapsed()))
+    } else {
+        (Stream::Plain(tcp), None)
+    };
+
+    Ok(TimedStream {
+        stream,
+        connection: NewConnection(Arc::new(ConnectionPhases {
+            dns,
+            connect,
+            tls,
+            established: started,
+            claimed: AtomicBool::new(false),
+        })),
+    })
+}
+
+enum Stream {
+    Plain(TcpStream),
+    Tls(Box<TlsStream<TcpStream>>),
+}
+
+// A connection that hands its setup phases to hyper
+struct TimedStream {
+    stream: Stream,
+    connection: NewConnection,
+}
+
+impl Connection for TimedStream {
+    fn connected(&self) -> Connected {
+        Connected::new().extra(self.connection.clone())
+    }
+}
+
+impl AsyncRead for TimedStream {
+    fn poll_read(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &mut ReadBuf<'_>) -> Poll<io::Result<()>> {
+        match &mut self.get_mut().stream {
+            Stream::Plain(stream) => Pin::new(stream).poll_read(cx, buf),
+            Stream::Tls(stream) => Pin::new(stream).poll_read(cx, buf),
+        }
+    }
+}
+
+impl AsyncWrite for TimedStream {
+    fn poll_write(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &[u8]) -> Poll<io::Result<usize>> {
+        match &mut self.get_mut().stream {
+            Stream::Plain(stream) => Pin::new(stream).poll_write(cx, buf),
+            Stream::Tls(stream) => Pin::new(stream).poll_write(cx, buf),
+        }
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        match &mut self.get_mut().stream {
+            Stream::Plain(stream) => Pin::new(stream).poll_flush(cx),
+            Stream::Tls(stream) => Pin::new(stream).poll_flush(cx),
+        }
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        match &mut self.get_mut().stream {
+            Stream::Plain(stream) => Pin::new(stream).poll_shutdown(cx),
+            Stream::Tls(stream) => Pin::new(stream).poll_shutdown(cx),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::http::error_class::classify_error;
+    use crate::http::test_server::{Reply, TestServer};
+    use crate::model::failure::ErrorClass;
+    use reqwest::Method;
+
+    fn get(url: &str) -> Request {
+        Request::new(Method::GET, url.parse().unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_connection_phases_new_and_pooled() {
+        let server = TestServer::start(|_| Reply::ok("hello")).await;
+        let client = TimedClient::new().unwrap();
+
+        let first = client.execute(&get(&format!("{}/a", server.url))).await.unwrap();
+        assert_eq!(&first.body[..], b"hello");
+        assert!(first.phases.connect.is_some());
+        assert!(first.phases.dns.is_none(), "an IP literal is not resolved");
+        assert!(first.phases.tls.is_none());
+
+        let pooled = client.execute(&get(&format!("{}/b", server.url))).await.unwrap();
+        assert_eq!(pooled.phases.connect, None);
+        let requests = server.requests();
+        assert_eq!(requests[0].connection, requests[1].connection);
+
+        // A pool of its own opens a new connection, and resolves a host name
+        let fresh = client.new_pool().execute(&get(&server.url.replace("127.0.0.1", "localhost"))).await.unwrap();
+        assert!(fresh.phases.connect.is_some());
+        assert!(fresh.phases.dns.is_some());
+        assert_ne!(server.requests()[2].connection, requests[0].connection);
+    }
+
+    #[tokio::test]
+    async fn test_body_read_timeout() {
+        let server = TestServer::start(|_| Reply::ok("never sent").stall_body()).await;
+        let client = TimedClient { request_timeout: Duration::from_millis(200), ..TimedClient::new().unwrap() };
+
+        let error = client.execute(&get(&server.url)).await.unwrap_err();
+        assert_eq!(error.to_string(), BODY_READ_CONTEXT);
+        assert_eq!(classify_error(&error), ErrorClass::RequestTimeout);
+    }
+
+    #[tokio::test]
+    async fn test_url_credentials_become_basic_auth() {
+        let server = TestServer::start(|_| Reply::ok("")).await;
+        let client = TimedClient::new().unwrap();
+
+        client.execute(&get(&server.url.replace("http://", "http://user:secret@"))).await.unwrap();
+        let request = &server.requests()[0];
+        assert_eq!(request.header("authorization"), Some("Basic dXNlcjpzZWNyZXQ="));
+        assert_eq!(request.path, "/");
+    }
+}
@@ -11,8 +11,8 @@ pub use model::{
     state::AppState,
     test::{
         TestConfig, TestResult, TestStatus, TestType, TestMetrics, TestUpdate,
-        ApiTestConfig, LoadTestConfig, StressTestConfig, ApiTest, // Use ApiTest
-        RequestResult, ApiRequestResult
+        ApiTestConfig, LoadTestConfig, StressTestConfig, ApiTest, ApiTestResult, // Use ApiTest
+        RequestBody, RequestTemplate, RequestResult, ApiRequestResult
     },
     time_series::TimeSeriesPoint,
 };
@@ -1,4 +1,5 @@
 use std::env;
+use std::path::PathBuf;
 
 /// Server configuration
 #[derive(Clone, Debug)]
@@ -118,6 +119,11 @@ pub fn load_security_config() -> SecurityConfig {
     }
 }
 
+// Pure function to get the directory that file bodies, feeders and schema files are read from
+pub fn fixtures_dir() -> PathBuf {
+    PathBuf::from(get_env_or_default("FIXTURES_DIR", "fixtures"))
+}
+
 // Pure function to load the complete application configuration
 pub fn load_config() -> AppConfig {
     AppConfig {
@@ -0,0 +1,198 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt;
+
+/// Distinct error messages tracked per accumulator; a new message past this evicts the least
+/// frequent one
+pub const MAX_DISTINCT_ERRORS: usize = 20;
+
+/// Why a load/stress request failed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorClass {
+    ConnectRefused,
+    Dns,
+    Tls,
+    ConnectTimeout,
+    RequestTimeout,
+    ConnectionReset,
+    BodyRead,
+    #[serde(rename = "http_4xx")]
+    Http4xx,
+    #[serde(rename = "http_5xx")]
+    Http5xx,
+    /// The response arrived but broke a body, response time or extraction check
+    AssertionFailed,
+    /// A transport error none of the other classes describe
+    Other,
+}
+
+impl ErrorClass {
+    /// Class of a response rejected for its status
+    pub fn from_status(status: u16) -> Self {
+        match status {
+            500.. => ErrorClass::Http5xx,
+            400..=499 => ErrorClass::Http4xx,
+            _ => ErrorClass::AssertionFailed,
+        }
+    }
+}
+
+impl fmt::Display for ErrorClass {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            ErrorClass::ConnectRefused => "connect_refused",
+            ErrorClass::Dns => "dns",
+            ErrorClass::Tls => "tls",
+            ErrorClass::ConnectTimeout => "connect_timeout",
+            ErrorClass::RequestTimeout => "request_timeout",
+            ErrorClass::ConnectionReset => "connection_reset",
+            ErrorClass::BodyRead => "body_read",
+            ErrorClass::Http4xx => "http_4xx",
+            ErrorClass::Http5xx => "http_5xx",
+            ErrorClass::AssertionFailed => "assertion_failed",
+            ErrorClass::Other => "other",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// A response that does not count as a success
+#[derive(Debug, Clone, PartialEq)]
+pub struct RequestFailure {
+    pub class: ErrorClass,
+    pub message: String,
+}
+
+/// One distinct error message and how often it occurred
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ErrorSummary {
+    pub class: ErrorClass,
+    pub message: String,
+    /// Outermost context of the first transport error, naming the request URL left out of `message`
+    pub sample: Option<String>,
+    /// Occurrences; a message that took the slot of an evicted one also carries the evicted
+    /// count, so it may be overestimated by that much
+    pub count: u32,
+    pub first_seen: DateTime<Utc>,
+    pub last_seen: DateTime<Utc>,
+}
+
+/// Error counts per class and the most frequent distinct messages, at most `MAX_DISTINCT_ERRORS`,
+/// kept with space-saving counting so a frequent message that starts late still shows up
+#[derive(Debug, Clone, Default)]
+pub struct ErrorBreakdown {
+    classes: HashMap<ErrorClass, u32>,
+    messages: Vec<ErrorSummary>,
+}
+
+impl ErrorBreakdown {
+    pub fn record(&mut self, class: ErrorClass, message: &str, sample: Option<&str>, at: DateTime<Utc>) {
+        *self.classes.entry(class).or_insert(0) += 1;
+        if let Some(summary) = self.messages.iter_mut().find(|summary| summary.class == class && summary.message == message) {
+            summary.count += 1;
+            summary.last_seen = at;
+        } else {
+            let mut summary = ErrorSummary {
+                class,
+                message: message.to_string(),
+                sample: sample.map(str::to_string),
+                count: 1,
+                first_seen: at,
+                last_seen: at,
+            };
+            if self.messages.len() < MAX_DISTINCT_ERRORS {
+                self.messages.push(summary);
+            } else if let Some(evicted) = self
+                .messages
+                .iter_mut()
+                .min_by(|a, b| a.count.cmp(&b.count).then(a.last_seen.cmp(&b.last_seen)))
+            {
+                summary.count += evicted.count;
+                *evicted = summary;
+            }
+        }
+    }
+
+    pub fn classes(&self) -> &HashMap<ErrorClass, u32> {
+        &self.classes
+    }
+
+    /// Tracked messages, most frequent first
+    pub fn top_errors(&self) -> Vec<ErrorSummary> {
+        let mut messages = self.messages.clone();
+        messages.sort_by(|a, b| b.count.cmp(&a.count).then(a.first_seen.cmp(&b.first_seen)));
+        messages
+    }
+
+    /// "12 requests failed (8 http_5xx, 4 connect_refused)", `None` without errors
+    pub fn describe(&self) -> Option<String> {
+        let total: u32 = self.classes.values().sum();
+        if total == 0 {
+            return None;
+        }
+        let mut classes: Vec<_> = self.classes.iter().map(|(class, count)| (*count, class.to_string())).collect();
+        classes.sort_by(|a, b| b.0.cmp(&a.0).then_with(|| a.1.cmp(&b.1)));
+        let parts: Vec<String> = classes.iter().map(|(count, name)| format!("{} {}", count, name)).collect();
+        Some(format!("{} requests failed ({})", total, parts.join(", ")))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    #[test]
+    fn test_error_breakdown() {
+        let start = Utc::now();
+        let mut breakdown = ErrorBreakdown::default();
+        breakdown.record(ErrorClass::ConnectRefused, "connection refused", Some("Failed to send GET request to http://a/"), start);
+        breakdown.record(ErrorClass::ConnectRefused, "connection refused", Some("Failed to send GET request to http://b/"), start);
+        for second in 1..=3 {
+            breakdown.record(ErrorClass::Http5xx, "Unexpected status 503", None, start + Duration::seconds(second));
+        }
+        for index in 0..MAX_DISTINCT_ERRORS {
+            breakdown.record(ErrorClass::AssertionFailed, &format!("missing field {}", index), None, start);
+        }
+
+        assert_eq!(breakdown.classes()[&ErrorClass::Http5xx], 3);
+        assert_eq!(breakdown.classes()[&ErrorClass::AssertionFailed], MAX_DISTINCT_ERRORS as u32);
+        let top = breakdown.top_errors();
+        assert_eq!(top.len(), MAX_DISTINCT_ERRORS);
+        assert_eq!(top[0].message, "Unexpected status 503");
+        assert_eq!((top[0].first_seen, top[0].last_seen), (start + Duration::seconds(1), start + Duration::seconds(3)));
+        assert_eq!(top[1].count, 2);
+        assert_eq!(top[1].sample.as_deref(), Some("Failed to send GET request to http://a/"));
+        assert_eq!(
+            breakdown.describe().unwrap(),
+            format!("{} requests failed ({} assertion_failed, 3 http_5xx, 2 connect_refused)", 25, MAX_DISTINCT_ERRORS)
+        );
+        assert_eq!(serde_json::to_string(&ErrorClass::Http4xx).unwrap(), format!("\"{}\"", ErrorClass::Http4xx));
+        assert_eq!(ErrorClass::from_status(404), ErrorClass::Http4xx);
+    }
+
+    #[test]
+    fn test_late_dominant_error_evicts_rare_ones() {
+        let start = Utc::now();
+        let mut breakdown = ErrorBreakdown::default();
+        for index in 0..MAX_DISTINCT_ERRORS {
+            breakdown.record(ErrorClass::Http4xx, &format!("rare {}", index), None, start);
+        }
+        breakdown.record(ErrorClass::Http4xx, "rare 0", None, start);
+        for second in 1..=50 {
+            breakdown.record(ErrorClass::ConnectionReset, "connection reset by peer", None, start + Duration::seconds(second));
+        }
+
+        let top = breakdown.top_errors();
+        assert_eq!(top.len(), MAX_DISTINCT_ERRORS);
+        assert_eq!(top[0].message, "connection reset by peer");
+        // Overestimated by at most the count of the message it replaced
+        assert!((50..=51).contains(&top[0].count), "{}", top[0].count);
+        assert_eq!(top[0].first_seen, start + Duration::seconds(1));
+        assert_eq!(top[1].message, "rare 0");
+        assert!(!top.iter().any(|summary| summary.message == "rare 1"));
+        assert_eq!(breakdown.classes()[&ErrorClass::ConnectionReset], 50);
+    }
+}
@@ -0,0 +1,122 @@
+use hdrhistogram::Histogram;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+// Highest trackable response time, in microseconds; slower responses are recorded as this
+const MAX_TRACKABLE_US: u64 = 60 * 60 * 1_000_000;
+
+// Significant decimal digits kept for every recorded value
+const SIGNIFICANT_DIGITS: u8 = 3;
+
+/// Response time percentiles, in ms
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct LatencyPercentiles {
+    pub p50: f64,
+    pub p90: f64,
+    pub p95: f64,
+    pub p99: f64,
+    pub p999: f64,
+}
+
+/// Mergeable response time histogram of fixed size, however many values it records
+#[derive(Debug, Clone)]
+pub struct LatencyHistogram {
+    histogram: Histogram<u64>,
+}
+
+impl Default for LatencyHistogram {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl LatencyHistogram {
+    pub fn new() -> Self {
+        Self {
+            histogram: Histogram::new_with_bounds(1, MAX_TRACKABLE_US, SIGNIFICANT_DIGITS)
+                .expect("static histogram bounds are valid"),
+        }
+    }
+
+    pub fn record(&mut self, duration: Duration) {
+        let micros = u64::try_from(duration.as_micros()).unwrap_or(u64::MAX);
+        self.histogram.saturating_record(micros.max(1));
+    }
+
+    /// Add every value recorded by another histogram
+    pub fn merge(&mut self, other: &LatencyHistogram) {
+        // Both histograms share the same bounds, so adding cannot fail
+        let _ = self.histogram.add(&other.histogram);
+    }
+
+    pub fn len(&self) -> u64 {
+        self.histogram.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.histogram.is_empty()
+    }
+
+    pub fn min_ms(&self) -> f64 {
+        if self.is_empty() { 0.0 } else { to_ms(self.histogram.min()) }
+    }
+
+    pub fn max_ms(&self) -> f64 {
+        to_ms(self.histogram.max())
+    }
+
+    pub fn mean_ms(&self) -> f64 {
+        self.histogram.mean() / 1000.0
+    }
+
+    /// Response time at quantile `quantile` (0.0 to 1.0), in ms
+    pub fn value_at_quantile_ms(&self, quantile: f64) -> f64 {
+        to_ms(self.histogram.value_at_quantile(quantile))
+    }
+
+    /// The standard percentiles, `None` while nothing was recorded
+    pub fn percentiles(&self) -> Option<LatencyPercentiles> {
+        if self.is_empty() {
+            return None;
+        }
+        Some(LatencyPercentiles {
+            p50: self.value_at_quantile_ms(0.5),
+            p90: self.value_at_quantile_ms(0.9),
+            p95: self.value_at_quantile_ms(0.95),
+            p99: self.value_at_quantile_ms(0.99),
+            p999: self.value_at_quantile_ms(0.999),
+        })
+    }
+}
+
+fn to_ms(micros: u64) -> f64 {
+    micros as f64 / 1000.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_latency_histogram() {
+        let mut fast = LatencyHistogram::new();
+        for ms in 1..=900 {
+            fast.record(Duration::from_millis(ms));
+        }
+        let mut slow = LatencyHistogram::new();
+        for ms in 901..=1000 {
+            slow.record(Duration::from_millis(ms));
+        }
+        slow.record(Duration::from_secs(2 * 60 * 60));
+        fast.merge(&slow);
+
+        assert_eq!(fast.len(), 1001);
+        assert_eq!(fast.min_ms(), 1.0);
+        assert!((fast.max_ms() - 3_600_000.0).abs() / 3_600_000.0 < 0.001);
+        let percentiles = fast.percentiles().unwrap();
+        assert!((percentiles.p50 - 501.0).abs() < 1.0, "p50 was {}", percentiles.p50);
+        assert!((percentiles.p99 - 991.0).abs() < 1.0, "p99 was {}", percentiles.p99);
+        assert!(LatencyHistogram::new().percentiles().is_none());
+        assert_eq!(LatencyHistogram::new().min_ms(), 0.0);
+    }
+}
@@ -1,8 +1,9 @@
 use std::time::Duration;
 use std::collections::HashMap;
-use std::sync::atomic::{AtomicU32, Ordering};
-use std::sync::Arc;
+use std::sync::atomic::{AtomicU32, AtomicU64, Ordering};
+use std::sync::{Arc, OnceLock};
 use tokio::sync::Mutex;
+use tokio::time::Instant;
 
 /// Represents metrics collected during testing
 #[derive(Debug, Clone)]
@@ -27,6 +28,42 @@ pub struct MetricsSummary {
     pub status_codes: HashMap<u16, u32>,
 }
 
+/// Counters updated by the load/stress engine outside of individual request results
+#[derive(Debug, Default)]
+pub struct EngineStats {
+    /// Arrival-rate iterations skipped because `max_in_flight` iterations were pending
+    pub dropped_iterations: AtomicU64,
+    /// Arrival-rate iterations started more than one interval after their scheduled time
+    pub late_iterations: AtomicU64,
+    /// Virtual users currently running iterations
+    pub active_users: AtomicU64,
+    // Bits of the f64 virtual users or arrival rate currently aimed for
+    target_load: AtomicU64,
+    // When the executor started sending, after the test plan was prepared
+    started: OnceLock<Instant>,
+}
+
+impl EngineStats {
+    /// Record the virtual users or arrival rate the engine currently aims for
+    pub fn set_target_load(&self, target: f64) {
+        self.target_load.store(target.to_bits(), Ordering::Relaxed);
+    }
+
+    pub fn target_load(&self) -> f64 {
+        f64::from_bits(self.target_load.load(Ordering::Relaxed))
+    }
+
+    /// Record that the executor starts now, the origin of its stage profile, and return that time
+    pub fn mark_started(&self) -> Instant {
+        *self.started.get_or_init(Instant::now)
+    }
+
+    /// When the executor started, `None` while the test is still being prepared
+    pub fn started(&self) -> Option<Instant> {
+        self.started.get().copied()
+    }
+}
+
 /// Thread-safe metrics collection structure
 #[derive(Clone)]
 pub struct OptimizedMetrics {
@@ -89,6 +126,12 @@ impl OptimizedMetrics {
     }
 }
 
+impl Default for OptimizedMetrics {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 /// Snapshot of test metrics at a point in time
 #[derive(Clone)]
 pub struct TestMetricsSnapshot {
@@ -144,7 +187,7 @@ pub fn new_metrics() -> Metrics {
 /// Add a request to metrics
 pub fn add_request(metrics: &mut Metrics, duration: Duration, status: u16, error: Option<&str>) {
     metrics.requests += 1;
-    if status >= 200 && status < 400 {
+    if (200..400).contains(&status) {
         metrics.success += 1;
     } else {
         metrics.errors += 1;
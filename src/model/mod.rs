@@ -1,13 +1,16 @@
 pub mod config;
 pub mod error;
+pub mod failure;
+pub mod histogram;
 pub mod metrics;
+pub mod phase;
 pub mod state;
 pub mod test;
 pub mod time_series;
 pub mod utils;
 
 // Re-export common types
-pub use test::{TestConfig, TestResult, TestStatus, TestType, TestMetrics, TestUpdate, ApiTestConfig, LoadTestConfig, StressTestConfig, ApiTest, RequestResult, ApiRequestResult}; // Use ApiTest
+pub use test::{TestConfig, TestResult, TestStatus, TestType, TestMetrics, TestUpdate, ApiTestConfig, LoadTestConfig, StressTestConfig, ApiTest, ApiTestResult, RequestBody, RequestTemplate, RequestResult, ApiRequestResult}; // Use ApiTest
 pub use state::AppState;
 pub use error::AppError;
 pub use config::AppConfig;
\ No newline at end of file
@@ -0,0 +1,137 @@
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+use crate::model::histogram::{LatencyHistogram, LatencyPercentiles};
+
+/// Time a load/stress request spent in each phase, summed over its redirect hops
+///
+/// `dns`, `connect` and `tls` are only set when the request opened a new connection (`dns` for a
+/// host name, `tls` for https); a request sent over a pooled connection skips them.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct PhaseTimings {
+    pub dns: Option<Duration>,
+    pub connect: Option<Duration>,
+    pub tls: Option<Duration>,
+    /// From sending the request until the response headers arrived, connection setup excluded
+    pub ttfb: Duration,
+    /// Reading the response body
+    pub download: Duration,
+}
+
+impl PhaseTimings {
+    /// Add the phases of another redirect hop
+    pub fn add(&mut self, other: &PhaseTimings) {
+        fn sum(a: Option<Duration>, b: Option<Duration>) -> Option<Duration> {
+            match (a, b) {
+                (Some(a), Some(b)) => Some(a + b),
+                (a, b) => a.or(b),
+            }
+        }
+        self.dns = sum(self.dns, other.dns);
+        self.connect = sum(self.connect, other.connect);
+        self.tls = sum(self.tls, other.tls);
+        self.ttfb += other.ttfb;
+        self.download += other.download;
+    }
+}
+
+/// Statistics of one request phase, in ms
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct PhaseStats {
+    /// Requests that went through the phase
+    pub count: u64,
+    pub average: f64,
+    pub max: f64,
+    pub percentiles: LatencyPercentiles,
+}
+
+/// Per-phase statistics of load/stress requests; a connection phase is `None` until a request
+/// goes through it
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct PhaseMetrics {
+    pub dns: Option<PhaseStats>,
+    pub connect: Option<PhaseStats>,
+    pub tls: Option<PhaseStats>,
+    pub ttfb: Option<PhaseStats>,
+    pub download: Option<PhaseStats>,
+}
+
+/// One latency histogram per request phase
+#[derive(Debug, Clone, Default)]
+pub struct PhaseHistograms {
+    dns: LatencyHistogram,
+    connect: LatencyHistogram,
+    tls: LatencyHistogram,
+    ttfb: LatencyHistogram,
+    download: LatencyHistogram,
+}
+
+impl PhaseHistograms {
+    pub fn record(&mut self, timings: &PhaseTimings) {
+        let connection_phases = [
+            (&mut self.dns, timings.dns),
+            (&mut self.connect, timings.connect),
+            (&mut self.tls, timings.tls),
+        ];
+        for (histogram, duration) in connection_phases {
+            if let Some(duration) = duration {
+                histogram.record(duration);
+            }
+        }
+        self.ttfb.record(timings.ttfb);
+        self.download.record(timings.download);
+    }
+
+    /// Statistics of every phase, `None` before the first timed request
+    pub fn metrics(&self) -> Option<PhaseMetrics> {
+        if self.ttfb.is_empty() {
+            return None;
+        }
+        Some(PhaseMetrics {
+            dns: phase_stats(&self.dns),
+            connect: phase_stats(&self.connect),
+            tls: phase_stats(&self.tls),
+            ttfb: phase_stats(&self.ttfb),
+            download: phase_stats(&self.download),
+        })
+    }
+}
+
+fn phase_stats(histogram: &LatencyHistogram) -> Option<PhaseStats> {
+    Some(PhaseStats {
+        count: histogram.len(),
+        average: histogram.mean_ms(),
+        max: histogram.max_ms(),
+        percentiles: histogram.percentiles()?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_phase_histograms() {
+        let ms = Duration::from_millis;
+        let mut redirected = PhaseTimings { dns: Some(ms(5)), connect: Some(ms(10)), ttfb: ms(20), download: ms(1), ..Default::default() };
+        redirected.add(&PhaseTimings { connect: Some(ms(12)), tls: Some(ms(30)), ttfb: ms(40), download: ms(3), ..Default::default() });
+        assert_eq!(
+            redirected,
+            PhaseTimings { dns: Some(ms(5)), connect: Some(ms(22)), tls: Some(ms(30)), ttfb: ms(60), download: ms(4) }
+        );
+
+        let mut histograms = PhaseHistograms::default();
+        assert!(histograms.metrics().is_none());
+        histograms.record(&redirected);
+        histograms.record(&PhaseTimings { ttfb: ms(100), download: ms(2), ..Default::default() });
+        let metrics = histograms.metrics().unwrap();
+        assert_eq!(metrics.connect.unwrap().count, 1);
+        assert_eq!(metrics.ttfb.unwrap().count, 2);
+        assert!((metrics.ttfb.unwrap().average - 80.0).abs() < 0.1);
+        assert!((metrics.download.unwrap().max - 4.0).abs() < 0.01);
+
+        let mut reused = PhaseHistograms::default();
+        reused.record(&PhaseTimings { ttfb: ms(7), ..Default::default() });
+        assert!(reused.metrics().unwrap().dns.is_none());
+    }
+}
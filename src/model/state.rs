@@ -194,10 +194,13 @@ impl AppState {
         format!("test-{}", id)
     }
     
-    /// Add a test result to the state
+    /// Add a test result to the state, replacing any existing result with the same ID
     pub async fn add_test_result(&self, result: TestResult) {
         let mut results = self.test_results.lock().await;
-        results.push(result);
+        match results.iter_mut().find(|r| r.id == result.id) {
+            Some(existing) => *existing = result,
+            None => results.push(result),
+        }
     }
     
     /// Get all time series points
@@ -3,18 +3,34 @@ use std::collections::HashMap;
 use std::time::Duration;
 use serde_json::Value;
 use std::fmt;
+use std::sync::Arc;
 use chrono;
 
+use crate::model::failure::{ErrorClass, ErrorSummary, RequestFailure};
+use crate::model::histogram::{LatencyHistogram, LatencyPercentiles};
+use crate::model::phase::{PhaseMetrics, PhaseTimings};
+use crate::model::utils::template::{interpolate, interpolate_json, interpolate_map};
+
 pub mod api_test;
+pub mod breakpoint_test;
 pub mod load_test;
 pub mod stress_test;
 
 // Re-export types with unique names to avoid conflicts
 pub use api_test::ApiTestConfig;
-pub use load_test::LoadTestConfig;
-pub use stress_test::StressTestConfig;
+pub use breakpoint_test::{
+    create_test_config_from_breakpoint, BreakpointReport, BreakpointStep, BreakpointTestConfig, SloCriteria,
+};
+pub use load_test::{ArrivalRate, LoadTestConfig, SessionOptions, WeightedEndpoint};
+pub use load_test::har::{HarImport, HarImportOptions, import_har};
+pub use load_test::sitemap::{SitemapOrder, SitemapSource};
+pub use load_test::feeder::{FeederConfig, FeederSet};
+pub use load_test::success::{StatusRange, SuccessCriteria, SuccessPolicy};
+pub use load_test::think_time::ThinkTime;
+pub use load_test::adaptive::{AdaptiveConcurrency, AdaptiveSample};
+pub use stress_test::{Stage, StressTestConfig};
 pub use crate::model::time_series::TimeSeriesPoint;
-pub use api_test::ApiTest; 
+pub use api_test::{ApiTest, ApiTestResult, ExtractRule};
 
 // Common types used across all test types
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -23,6 +39,168 @@ pub struct TestConfig {
     pub concurrent_users: u32,
     pub duration_secs: u32,
     pub num_requests: u32,
+    /// Request sent to `target_url`
+    #[serde(flatten)]
+    pub request: RequestOptions,
+    /// Requests replayed in order by every iteration instead of `target_url`
+    pub requests: Option<Vec<RequestTemplate>>,
+    /// Endpoints picked by weight, one per iteration, instead of `target_url`
+    pub scenario: Option<Vec<WeightedEndpoint>>,
+    /// Sitemap whose URLs are requested with the `target_url` request options
+    pub sitemap: Option<SitemapSource>,
+    /// CSV/NDJSON files whose rows fill `{{column}}` placeholders, one row per iteration
+    pub feeders: Option<Vec<FeederConfig>>,
+    /// Start iterations at a fixed rate instead of keeping `concurrent_users` busy
+    pub arrival_rate: Option<ArrivalRate>,
+    /// Ramp profile for virtual users, or for the arrival rate when `arrival_rate` is set
+    pub stages: Option<Vec<Stage>>,
+    /// Pause after each request that sets no `think_time_ms` of its own
+    pub think_time: Option<ThinkTime>,
+    /// Minimum time between the starts of two iterations of the same virtual user
+    pub pacing_ms: Option<u64>,
+    /// Per-virtual-user cookie jar and connection pool
+    pub session: Option<SessionOptions>,
+    /// Statuses, body content and response time a response needs to count as a success
+    pub success: Option<SuccessPolicy>,
+    /// Adjust the number of virtual users to hold a latency or error rate target
+    pub adaptive: Option<AdaptiveConcurrency>,
+}
+
+impl TestConfig {
+    /// The single request sent to `target_url` when no sequence is given
+    pub fn target_request(&self) -> RequestTemplate {
+        RequestTemplate {
+            method: self.request.method.clone().unwrap_or_else(default_method),
+            url: self.target_url.clone(),
+            headers: self.request.headers.clone(),
+            body: self.request.body.clone(),
+            query_params: self.request.query_params.clone(),
+            think_time_ms: None,
+            extract: None,
+        }
+    }
+
+    /// Number of results each iteration produces
+    pub fn requests_per_iteration(&self) -> u32 {
+        if self.sitemap.is_some() || self.scenario.as_ref().is_some_and(|scenario| !scenario.is_empty()) {
+            return 1;
+        }
+        self.requests.as_ref().map_or(0, Vec::len).max(1) as u32
+    }
+}
+
+fn default_method() -> String {
+    "GET".to_string()
+}
+
+/// A request issued by the load/stress engine in place of a bare GET of `target_url`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RequestTemplate {
+    #[serde(default = "default_method")]
+    pub method: String,
+    pub url: String,
+    pub headers: Option<HashMap<String, String>>,
+    pub body: Option<RequestBody>,
+    /// Query parameters appended to `url`
+    pub query_params: Option<HashMap<String, String>>,
+    /// Pause after this request before the next one of a sequence
+    pub think_time_ms: Option<u64>,
+    /// Values stored in the virtual user's variables for later `{{name}}` placeholders
+    pub extract: Option<Vec<ExtractRule>>,
+}
+
+impl RequestTemplate {
+    /// Resolve `{{name}}` placeholders in the URL, headers, body and query parameters
+    pub fn interpolate(&self, vars: &HashMap<String, Value>) -> RequestTemplate {
+        RequestTemplate {
+            url: interpolate(&self.url, vars),
+            headers: self.headers.as_ref().map(|headers| interpolate_map(headers, vars)),
+            body: self.body.as_ref().map(|body| body.interpolate(vars)),
+            query_params: self.query_params.as_ref().map(|params| interpolate_map(params, vars)),
+            ..self.clone()
+        }
+    }
+}
+
+/// Method, headers, body and query parameters of the request sent to `target_url`
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RequestOptions {
+    /// HTTP method, GET when omitted
+    pub method: Option<String>,
+    pub headers: Option<HashMap<String, String>>,
+    pub body: Option<RequestBody>,
+    pub query_params: Option<HashMap<String, String>>,
+}
+
+/// Request body as written in a test definition.
+///
+/// Variants are tried in order, so a plain string is sent as-is, `{"form": {...}}` is sent
+/// form-urlencoded, `{"type": "file", "path": "..."}` is read from the fixtures directory,
+/// `{"json": ...}` sends the wrapped value, and any other JSON value is sent as JSON.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum RequestBody {
+    Text(String),
+    Form(FormBody),
+    File(FileBody),
+    WrappedJson(JsonBody),
+    Json(Value),
+}
+
+impl RequestBody {
+    /// Wrap a JSON value so that it serializes back to the same JSON body
+    pub fn from_json(value: Value) -> RequestBody {
+        match serde_json::from_value::<RequestBody>(value.clone()) {
+            Ok(RequestBody::Json(value)) => RequestBody::Json(value),
+            _ => RequestBody::WrappedJson(JsonBody { json: value }),
+        }
+    }
+
+    /// Resolve `{{name}}` placeholders in the body content
+    pub fn interpolate(&self, vars: &HashMap<String, Value>) -> RequestBody {
+        match self {
+            RequestBody::Text(text) => RequestBody::Text(interpolate(text, vars)),
+            RequestBody::Form(form) => RequestBody::Form(FormBody { form: interpolate_map(&form.form, vars) }),
+            RequestBody::File(file) => RequestBody::File(FileBody::new(interpolate(&file.path, vars))),
+            RequestBody::WrappedJson(wrapped) => RequestBody::WrappedJson(JsonBody { json: interpolate_json(&wrapped.json, vars) }),
+            RequestBody::Json(value) => RequestBody::Json(interpolate_json(value, vars)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct FormBody {
+    pub form: HashMap<String, String>,
+}
+
+/// Marks a body object as a file reference
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FileBodyType {
+    File,
+}
+
+/// A body read from a file, written `{"type": "file", "path": "..."}`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct FileBody {
+    #[serde(rename = "type")]
+    pub kind: FileBodyType,
+    /// Path relative to the fixtures directory (`FIXTURES_DIR`)
+    pub path: String,
+}
+
+impl FileBody {
+    pub fn new(path: impl Into<String>) -> Self {
+        FileBody { kind: FileBodyType::File, path: path.into() }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct JsonBody {
+    pub json: Value,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
@@ -30,6 +208,7 @@ pub enum TestType {
     Load,
     Stress,
     Api,
+    Breakpoint,
 }
 
 impl fmt::Display for TestType {
@@ -38,6 +217,7 @@ impl fmt::Display for TestType {
             TestType::Load => write!(f, "Load"),
             TestType::Stress => write!(f, "Stress"),
             TestType::Api => write!(f, "Api"),
+            TestType::Breakpoint => write!(f, "Breakpoint"),
         }
     }
 }
@@ -70,9 +250,42 @@ pub struct TestMetrics {
     pub average_response_time: f64,
     pub min_response_time: f64,
     pub max_response_time: f64,
+    /// Response time percentiles of every response received, `None` before the first one
+    #[serde(default)]
+    pub percentiles: Option<LatencyPercentiles>,
+    /// DNS, connect, TLS, time to first byte and download statistics of load/stress requests
+    #[serde(default)]
+    pub phases: Option<PhaseMetrics>,
+    /// Failed requests in percent, whether the response broke the success policy or never came
     pub error_rate: f64,
+    /// Responses that broke the success policy (unexpected status, body or response time)
+    #[serde(default)]
+    pub http_failures: u32,
+    /// Requests that got no response: connection, TLS or timeout errors
+    #[serde(default)]
+    pub network_failures: u32,
+    /// Failed requests per error class
+    #[serde(default)]
+    pub error_classes: HashMap<ErrorClass, u32>,
+    /// Most frequent distinct error messages, with when each was first and last seen
+    #[serde(default)]
+    pub top_errors: Vec<ErrorSummary>,
     pub requests_per_second: f64,
     pub status_codes: HashMap<u16, u32>,
+    /// Metrics of each weighted scenario endpoint, keyed by endpoint name
+    pub endpoints: Option<HashMap<String, TestMetrics>>,
+    /// Arrival-rate iterations skipped because too many were already in flight
+    #[serde(default)]
+    pub dropped_iterations: u64,
+    /// Arrival-rate iterations that started noticeably after their scheduled time
+    #[serde(default)]
+    pub late_iterations: u64,
+    /// Virtual users or arrival rate the engine was aiming for, following `stages` when set
+    #[serde(default)]
+    pub target_load: Option<f64>,
+    /// Virtual users running iterations when the metrics were taken
+    #[serde(default)]
+    pub active_users: Option<u64>,
 }
 
 impl Default for TestMetrics {
@@ -83,9 +296,20 @@ impl Default for TestMetrics {
             average_response_time: 0.0,
             min_response_time: 0.0,
             max_response_time: 0.0,
+            percentiles: None,
+            phases: None,
             error_rate: 0.0,
+            http_failures: 0,
+            network_failures: 0,
+            error_classes: HashMap::new(),
+            top_errors: Vec::new(),
             requests_per_second: 0.0,
             status_codes: HashMap::new(),
+            endpoints: None,
+            dropped_iterations: 0,
+            late_iterations: 0,
+            target_load: None,
+            active_users: None,
         }
     }
 }
@@ -101,6 +325,11 @@ pub struct TestResult {
     pub error: Option<String>,
     pub start_time: chrono::DateTime<chrono::Utc>,
     pub end_time: Option<chrono::DateTime<chrono::Utc>>,
+    /// Per-step outcomes, only present for API tests
+    pub api_results: Option<Vec<ApiTestResult>>,
+    /// Load steps and breaking point, only present for breakpoint tests
+    #[serde(default)]
+    pub breakpoint: Option<BreakpointReport>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -111,6 +340,11 @@ pub struct TestUpdate {
     pub progress: f32,
     pub metrics: Option<TestMetrics>,
     pub error: Option<String>,
+    /// Outcome of the API test step that triggered this update
+    pub api_result: Option<ApiTestResult>,
+    /// Breakpoint test load step that finished with this update
+    #[serde(default)]
+    pub breakpoint_step: Option<BreakpointStep>,
 }
 
 // --- Result Structs ---
@@ -120,6 +354,17 @@ pub struct TestUpdate {
 pub struct RequestResult {
     pub duration: Duration,
     pub status: u16,
+    /// Why the response does not count as a success, `None` when it does
+    pub failure: Option<RequestFailure>,
+    /// DNS, connect, TLS, time to first byte and download times, for phase-timed requests
+    pub phases: Option<PhaseTimings>,
+}
+
+/// A load/stress request result labelled with the scenario endpoint it was sent to
+#[derive(Debug)]
+pub struct RequestOutcome {
+    pub endpoint: Option<Arc<str>>,
+    pub result: anyhow::Result<RequestResult>,
 }
 
 // Result for API tests
@@ -128,6 +373,8 @@ pub struct ApiRequestResult {
     pub duration: Duration,
     pub status: u16,
     pub json: Option<Value>,
+    pub headers: HashMap<String, String>,
+    pub body: String,
 }
 
 
@@ -151,6 +398,8 @@ pub fn create_test_result(
         error,
         start_time: chrono::Utc::now(),
         end_time: None,
+        api_results: None,
+        breakpoint: None,
     }
 }
 
@@ -169,6 +418,8 @@ pub fn create_test_update(
         progress,
         metrics,
         error,
+        api_result: None,
+        breakpoint_step: None,
     }
 }
 
@@ -183,21 +434,8 @@ pub fn create_test_metrics(
         .map(|d| d.as_secs_f64())
         .sum::<f64>();
 
-    let avg_response_time_ms = if !durations.is_empty() {
-        (total_duration_secs * 1000.0) / durations.len() as f64
-    } else {
-        0.0
-    };
-
-    let min_response_time_ms = durations.iter()
-        .map(|d| d.as_secs_f64() * 1000.0)
-        .min_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal))
-        .unwrap_or(0.0);
-
-    let max_response_time_ms = durations.iter()
-        .map(|d| d.as_secs_f64() * 1000.0)
-        .max_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal))
-        .unwrap_or(0.0);
+    let mut histogram = LatencyHistogram::new();
+    durations.iter().for_each(|duration| histogram.record(*duration));
 
     let error_rate = if requests_completed > 0 {
         (errors as f64 / requests_completed as f64) * 100.0
@@ -214,30 +452,184 @@ pub fn create_test_metrics(
     TestMetrics {
         requests_completed,
         total_requests,
-        average_response_time: avg_response_time_ms,
-        min_response_time: min_response_time_ms,
-        max_response_time: max_response_time_ms,
+        average_response_time: histogram.mean_ms(),
+        min_response_time: histogram.min_ms(),
+        max_response_time: histogram.max_ms(),
+        percentiles: histogram.percentiles(),
+        phases: None,
         error_rate,
+        http_failures: 0,
+        network_failures: 0,
+        error_classes: HashMap::new(),
+        top_errors: Vec::new(),
         requests_per_second,
         status_codes,
+        endpoints: None,
+        dropped_iterations: 0,
+        late_iterations: 0,
+        target_load: None,
+        active_users: None,
     }
 }
 
+/// Virtual users of a load test that does not set `concurrent_users`
+pub const DEFAULT_LOAD_USERS: u32 = 10;
+
 // Helper functions for test configuration conversion
 pub fn create_test_config_from_load(config: &LoadTestConfig) -> TestConfig {
     TestConfig {
         target_url: config.target_url.clone(),
-        concurrent_users: config.concurrent_users.unwrap_or(1),
+        concurrent_users: config.concurrent_users.unwrap_or(DEFAULT_LOAD_USERS),
         duration_secs: 0,
         num_requests: config.num_requests,
+        request: config.request.clone(),
+        requests: config.requests.clone(),
+        scenario: config.scenario.clone(),
+        sitemap: config.sitemap.clone(),
+        feeders: config.feeders.clone(),
+        arrival_rate: config.arrival_rate.clone(),
+        stages: None,
+        think_time: config.think_time.clone(),
+        pacing_ms: config.pacing_ms,
+        session: config.session.clone(),
+        success: config.success.clone(),
+        adaptive: config.adaptive.clone(),
     }
 }
 
 pub fn create_test_config_from_stress(config: &StressTestConfig) -> TestConfig {
+    let stages = config.stages.as_deref().unwrap_or_default();
     TestConfig {
         target_url: config.target_url.clone(),
-        concurrent_users: config.concurrent_users,
-        duration_secs: config.duration_secs,
+        concurrent_users: match config.concurrent_users {
+            0 => config.adaptive.as_ref().and_then(|adaptive| adaptive.max_users).unwrap_or_else(|| stress_test::stages_peak(stages)),
+            users => users,
+        },
+        duration_secs: if stages.is_empty() { config.duration_secs } else { stress_test::stages_duration(stages) },
         num_requests: 0,
+        request: config.request.clone(),
+        requests: config.requests.clone(),
+        scenario: config.scenario.clone(),
+        sitemap: config.sitemap.clone(),
+        feeders: config.feeders.clone(),
+        arrival_rate: config.arrival_rate.clone(),
+        stages: config.stages.clone(),
+        think_time: config.think_time.clone(),
+        pacing_ms: config.pacing_ms,
+        session: config.session.clone(),
+        success: config.success.clone(),
+        adaptive: config.adaptive.clone(),
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_request_body_variants() {
+        let body: RequestBody = serde_json::from_value(json!("raw text")).unwrap();
+        assert_eq!(body, RequestBody::Text("raw text".to_string()));
+
+        let body: RequestBody = serde_json::from_value(json!({"title": "foo"})).unwrap();
+        assert_eq!(body, RequestBody::Json(json!({"title": "foo"})));
+
+        let body: RequestBody = serde_json::from_value(json!({"form": {"q": "rust"}})).unwrap();
+        assert!(matches!(body, RequestBody::Form(form) if form.form["q"] == "rust"));
+
+        let body: RequestBody = serde_json::from_value(json!({"type": "file", "path": "user.json"})).unwrap();
+        assert_eq!(body, RequestBody::File(FileBody::new("user.json")));
+
+        // Only the tagged form reads a file; a JSON payload with a `file` key stays JSON
+        let body: RequestBody = serde_json::from_value(json!({"file": "x"})).unwrap();
+        assert_eq!(body, RequestBody::Json(json!({"file": "x"})));
+        assert_eq!(
+            RequestBody::from_json(json!({"type": "file", "path": "x"})),
+            RequestBody::WrappedJson(JsonBody { json: json!({"type": "file", "path": "x"}) })
+        );
+
+        let body: RequestBody = serde_json::from_value(json!({"json": {"file": "not a path"}})).unwrap();
+        assert_eq!(body, RequestBody::WrappedJson(JsonBody { json: json!({"file": "not a path"}) }));
+    }
+
+    #[test]
+    fn test_sample_api_tests_deserialize() {
+        let path = concat!(env!("CARGO_MANIFEST_DIR"), "/test_examples/sample_restfulAPI_test.json");
+        let content = std::fs::read_to_string(path).unwrap();
+        let tests: Vec<ApiTest> = serde_json::from_str(&content).unwrap();
+
+        assert_eq!(tests.len(), 10);
+        assert!(matches!(tests[2].body, Some(RequestBody::Json(_))));
+    }
+
+    #[test]
+    fn test_load_config_request_options() {
+        let config: LoadTestConfig = serde_json::from_value(json!({
+            "target_url": "https://api.example.com/cart",
+            "num_requests": 100,
+            "method": "POST",
+            "headers": {"Authorization": "Bearer abc"},
+            "body": {"item": 7},
+            "query_params": {"currency": "EUR"}
+        }))
+        .unwrap();
+
+        let request = create_test_config_from_load(&config).target_request();
+        assert_eq!(request.method, "POST");
+        assert_eq!(request.url, "https://api.example.com/cart");
+        assert_eq!(request.body, Some(RequestBody::Json(json!({"item": 7}))));
+        assert_eq!(request.query_params.unwrap()["currency"], "EUR");
+
+        let config: LoadTestConfig = serde_json::from_value(json!({
+            "target_url": "https://example.com",
+            "num_requests": 10
+        }))
+        .unwrap();
+        let test_config = create_test_config_from_load(&config);
+        assert_eq!(test_config.target_request().method, "GET");
+        assert_eq!(test_config.concurrent_users, DEFAULT_LOAD_USERS);
+    }
+
+    #[test]
+    fn test_weighted_scenario_config() {
+        let config: StressTestConfig = serde_json::from_value(json!({
+            "concurrent_users": 10,
+            "duration_secs": 60,
+            "scenario": [
+                {"name": "list", "weight": 70, "url": "https://shop.example.com/products"},
+                {"name": "cart", "weight": 10, "method": "POST", "url": "https://shop.example.com/cart", "body": {"id": 1}}
+            ]
+        }))
+        .unwrap();
+
+        let test_config = create_test_config_from_stress(&config);
+        let scenario = test_config.scenario.as_ref().unwrap();
+        assert_eq!(scenario[0].request.method, "GET");
+        assert_eq!(scenario[1].request.body, Some(RequestBody::Json(json!({"id": 1}))));
+        assert_eq!(test_config.requests_per_iteration(), 1);
+    }
+
+    #[test]
+    fn test_arrival_rate_config() {
+        let config: LoadTestConfig = serde_json::from_value(json!({
+            "target_url": "https://example.com",
+            "num_requests": 600,
+            "arrival_rate": {"rate": 50}
+        }))
+        .unwrap();
+
+        let test_config = create_test_config_from_load(&config);
+        let arrival_rate = test_config.arrival_rate.as_ref().unwrap();
+        assert_eq!(arrival_rate.rate, 50.0);
+        assert_eq!(arrival_rate.max_in_flight, None);
+
+        let metrics: TestMetrics = serde_json::from_value(json!({
+            "requests_completed": 1, "total_requests": 1, "average_response_time": 1.0,
+            "min_response_time": 1.0, "max_response_time": 1.0, "error_rate": 0.0,
+            "requests_per_second": 1.0, "status_codes": {}, "endpoints": null
+        }))
+        .unwrap();
+        assert_eq!(metrics.dropped_iterations, 0);
+    }
+}
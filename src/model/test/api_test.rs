@@ -3,14 +3,75 @@ use std::collections::HashMap;
 use std::time::Duration;
 use serde_json::Value;
 
+use crate::model::test::{ApiRequestResult, RequestBody};
+use crate::model::utils::template::{interpolate, interpolate_map};
+
+pub mod assertion;
+pub mod extract;
+pub mod matching;
+pub mod openapi;
+pub mod postman;
+pub mod schema;
+
+pub use assertion::{Assertion, AssertionOp, AssertionResult, evaluate_assertion};
+pub use extract::{ExtractRule, ExtractSource, extract_variables};
+pub use matching::{Mismatch, MismatchKind, match_body, match_headers};
+pub use openapi::{OpenApiImport, OpenApiImportOptions, import_openapi, parse_document};
+pub use postman::import_postman;
+pub use schema::{SchemaSource, SchemaViolation, validate_response_schema, validate_schema};
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ApiTest {
     pub name: String,
     pub method: String,
     pub url: String,
     pub headers: Option<HashMap<String, String>>,
-    pub body: Option<String>,
+    pub body: Option<RequestBody>,
     pub expected_status: u16,
+    /// Expected JSON body, matched as a subset of the actual body
+    pub expected_body: Option<Value>,
+    /// Expected response headers, matched by case-insensitive name
+    pub expected_headers: Option<HashMap<String, String>>,
+    /// Values to store from the response for `{{name}}` placeholders in later tests
+    pub extract: Option<Vec<ExtractRule>>,
+    /// Targeted checks against the body, headers, status and response time
+    #[serde(default)]
+    pub assertions: Vec<Assertion>,
+    /// JSON Schema the response body must satisfy, inline or as a file path
+    pub schema: Option<SchemaSource>,
+    /// Skip TLS certificate verification, like `curl -k`
+    #[serde(default)]
+    pub insecure: bool,
+}
+
+// Pure function to create an API test with only the required fields set
+pub fn create_api_test(name: String, method: String, url: String, expected_status: u16) -> ApiTest {
+    ApiTest {
+        name,
+        method,
+        url,
+        headers: None,
+        body: None,
+        expected_status,
+        expected_body: None,
+        expected_headers: None,
+        extract: None,
+        assertions: Vec::new(),
+        schema: None,
+        insecure: false,
+    }
+}
+
+impl ApiTest {
+    /// Resolve `{{name}}` placeholders in the URL, headers and body
+    pub fn interpolate(&self, vars: &HashMap<String, Value>) -> ApiTest {
+        ApiTest {
+            url: interpolate(&self.url, vars),
+            headers: self.headers.as_ref().map(|headers| interpolate_map(headers, vars)),
+            body: self.body.as_ref().map(|body| body.interpolate(vars)),
+            ..self.clone()
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -18,10 +79,129 @@ pub struct ApiTestConfig {
     pub tests: Vec<ApiTest>,
 }
 
+/// Outcome of a single `ApiTest` step within an API test run
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApiTestResult {
+    pub name: String,
+    pub method: String,
+    pub url: String,
+    pub passed: bool,
+    pub expected_status: u16,
+    pub status: Option<u16>,
+    /// Response time in milliseconds
+    pub response_time: f64,
+    pub error: Option<String>,
+    /// Body and header differences found against the expectations
+    pub mismatches: Vec<Mismatch>,
+    /// Outcome of every assertion, in definition order
+    pub assertions: Vec<AssertionResult>,
+    /// JSON Schema validation errors of the response body
+    pub schema_errors: Vec<SchemaViolation>,
+    /// Variables extracted from this response
+    pub extracted: HashMap<String, Value>,
+    /// curl command reproducing the request of a failed step
+    pub curl: Option<String>,
+}
+
 #[derive(Debug, Clone)]
 pub struct RequestResult {
     pub status: u16,
     pub duration: Duration,
     pub error: Option<String>,
     pub json: Option<Value>,
-} 
\ No newline at end of file
+}
+
+// Pure function to evaluate an API response against the expectations of a test
+pub fn evaluate_api_test(test: &ApiTest, response: &ApiRequestResult) -> ApiTestResult {
+    let status_matches = response.status == test.expected_status;
+
+    let mut mismatches = Vec::new();
+    if let Some(expected_body) = &test.expected_body {
+        mismatches.extend(match_body(expected_body, response.json.as_ref(), &response.body));
+    }
+    if let Some(expected_headers) = &test.expected_headers {
+        mismatches.extend(match_headers(expected_headers, &response.headers));
+    }
+
+    let assertions: Vec<AssertionResult> = test.assertions
+        .iter()
+        .map(|assertion| evaluate_assertion(assertion, response))
+        .collect();
+    let failed_assertions = assertions.iter().filter(|result| !result.passed).count();
+
+    let schema_errors = test.schema
+        .as_ref()
+        .map(|schema| validate_response_schema(schema, response))
+        .unwrap_or_default();
+
+    let mut errors = Vec::new();
+    if !status_matches {
+        errors.push(format!("Expected status {}, got {}", test.expected_status, response.status));
+    }
+    if !mismatches.is_empty() {
+        errors.push(format!("{} body/header mismatches", mismatches.len()));
+    }
+    if failed_assertions > 0 {
+        errors.push(format!("{} of {} assertions failed", failed_assertions, assertions.len()));
+    }
+    if !schema_errors.is_empty() {
+        errors.push(format!("{} schema validation errors", schema_errors.len()));
+    }
+
+    let passed = errors.is_empty();
+    let error = if passed { None } else { Some(errors.join("; ")) };
+
+    ApiTestResult {
+        name: test.name.clone(),
+        method: test.method.clone(),
+        url: test.url.clone(),
+        passed,
+        expected_status: test.expected_status,
+        status: Some(response.status),
+        response_time: response.duration.as_secs_f64() * 1000.0,
+        error,
+        mismatches,
+        assertions,
+        schema_errors,
+        extracted: HashMap::new(),
+        curl: None,
+    }
+}
+
+// Pure function to build the result for a test whose request could not be completed
+pub fn failed_api_test(test: &ApiTest, duration: Duration, error: String) -> ApiTestResult {
+    ApiTestResult {
+        name: test.name.clone(),
+        method: test.method.clone(),
+        url: test.url.clone(),
+        passed: false,
+        expected_status: test.expected_status,
+        status: None,
+        response_time: duration.as_secs_f64() * 1000.0,
+        error: Some(error),
+        mismatches: Vec::new(),
+        assertions: Vec::new(),
+        schema_errors: Vec::new(),
+        extracted: HashMap::new(),
+        curl: None,
+    }
+}
+
+// Pure function to record extracted variables on a step result; failed extractions fail the step
+pub fn apply_extraction(mut result: ApiTestResult, test: &ApiTest, response: &ApiRequestResult) -> ApiTestResult {
+    let Some(rules) = &test.extract else {
+        return result;
+    };
+
+    let (extracted, errors) = extract_variables(rules, response);
+    result.extracted = extracted;
+    if !errors.is_empty() {
+        result.passed = false;
+        let extraction_error = errors.join("; ");
+        result.error = Some(match result.error.take() {
+            Some(error) => format!("{}; {}", error, extraction_error),
+            None => extraction_error,
+        });
+    }
+    result
+}
@@ -0,0 +1,379 @@
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fmt;
+use std::str::FromStr;
+
+use crate::model::test::ApiRequestResult;
+use crate::model::test::api_test::matching::value_type;
+use crate::model::utils::json_path;
+use crate::model::utils::template::value_to_text;
+
+/// Operators supported by API test assertions
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AssertionOp {
+    Eq,
+    Ne,
+    Gt,
+    Ge,
+    Lt,
+    Le,
+    Contains,
+    Matches,
+    Exists,
+    Type,
+}
+
+impl FromStr for AssertionOp {
+    type Err = String;
+
+    fn from_str(op: &str) -> Result<Self, Self::Err> {
+        match op.to_lowercase().as_str() {
+            "eq" | "==" => Ok(AssertionOp::Eq),
+            "ne" | "!=" => Ok(AssertionOp::Ne),
+            "gt" | ">" => Ok(AssertionOp::Gt),
+            "ge" | ">=" => Ok(AssertionOp::Ge),
+            "lt" | "<" => Ok(AssertionOp::Lt),
+            "le" | "<=" => Ok(AssertionOp::Le),
+            "contains" => Ok(AssertionOp::Contains),
+            "matches" => Ok(AssertionOp::Matches),
+            "exists" => Ok(AssertionOp::Exists),
+            "type" | "is" => Ok(AssertionOp::Type),
+            _ => Err(format!("Unknown assertion operator: {}", op)),
+        }
+    }
+}
+
+impl fmt::Display for AssertionOp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let op = match self {
+            AssertionOp::Eq => "==",
+            AssertionOp::Ne => "!=",
+            AssertionOp::Gt => ">",
+            AssertionOp::Ge => ">=",
+            AssertionOp::Lt => "<",
+            AssertionOp::Le => "<=",
+            AssertionOp::Contains => "contains",
+            AssertionOp::Matches => "matches",
+            AssertionOp::Exists => "exists",
+            AssertionOp::Type => "type",
+        };
+        write!(f, "{}", op)
+    }
+}
+
+/// A targeted check against an API response.
+///
+/// `target` is a JSON path into the body (`$.data[0].id`), `status`,
+/// `response_time` (milliseconds), `body` (raw text) or `header.<name>`.
+/// Assertions can be written as objects or as expressions like
+/// `"$.items.length > 0"` and `"response_time < 200ms"`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "AssertionDef")]
+pub struct Assertion {
+    pub target: String,
+    pub op: AssertionOp,
+    pub value: Option<Value>,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum AssertionDef {
+    Expression(String),
+    Structured {
+        target: String,
+        op: AssertionOp,
+        #[serde(default)]
+        value: Option<Value>,
+    },
+}
+
+impl TryFrom<AssertionDef> for Assertion {
+    type Error = String;
+
+    fn try_from(def: AssertionDef) -> Result<Self, Self::Error> {
+        match def {
+            AssertionDef::Expression(expression) => expression.parse(),
+            AssertionDef::Structured { target, op, value } => Ok(Assertion {
+                value: value.map(|value| expected_value(&target, value)),
+                target,
+                op,
+            }),
+        }
+    }
+}
+
+// Targets measured in milliseconds, compared against duration strings
+fn is_duration_target(target: &str) -> bool {
+    matches!(target, "response_time" | "duration")
+}
+
+// The value to compare a target with; only durations accept strings such as "200ms"
+fn expected_value(target: &str, value: Value) -> Value {
+    if is_duration_target(target) { parse_duration_value(&value) } else { value }
+}
+
+// Turn duration strings such as "200ms" or "1.5s" into milliseconds
+fn parse_duration_value(value: &Value) -> Value {
+    let Value::String(text) = value else {
+        return value.clone();
+    };
+    let text = text.trim();
+    let millis = if let Some(number) = text.strip_suffix("ms") {
+        number.trim().parse::<f64>().ok()
+    } else if let Some(number) = text.strip_suffix('s') {
+        number.trim().parse::<f64>().ok().map(|secs| secs * 1000.0)
+    } else {
+        None
+    };
+    millis.map(Value::from).unwrap_or_else(|| value.clone())
+}
+
+// Parse the literal on the right-hand side of an expression
+fn parse_literal(text: &str) -> Value {
+    let text = text.trim();
+    serde_json::from_str::<Value>(text).unwrap_or_else(|_| Value::String(text.to_string()))
+}
+
+impl FromStr for Assertion {
+    type Err = String;
+
+    fn from_str(expression: &str) -> Result<Self, Self::Err> {
+        let expression = expression.trim();
+        let mut parts = expression.splitn(3, char::is_whitespace);
+        let target = parts.next().filter(|t| !t.is_empty())
+            .ok_or_else(|| "Empty assertion expression".to_string())?;
+        let op = parts.next()
+            .ok_or_else(|| format!("Missing operator in assertion: {}", expression))?
+            .parse::<AssertionOp>()?;
+        let value = parts
+            .next()
+            .map(str::trim)
+            .filter(|v| !v.is_empty())
+            .map(|text| expected_value(target, parse_literal(text)));
+
+        if value.is_none() && op != AssertionOp::Exists {
+            return Err(format!("Missing value in assertion: {}", expression));
+        }
+
+        Ok(Assertion { target: target.to_string(), op, value })
+    }
+}
+
+impl fmt::Display for Assertion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.value {
+            Some(value) => write!(f, "{} {} {}", self.target, self.op, value),
+            None => write!(f, "{} {}", self.target, self.op),
+        }
+    }
+}
+
+/// Outcome of evaluating one assertion
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AssertionResult {
+    pub assertion: String,
+    pub passed: bool,
+    pub actual: Option<Value>,
+    pub message: Option<String>,
+}
+
+// Resolve the value an assertion target refers to
+fn resolve_target(target: &str, response: &ApiRequestResult) -> Result<Option<Value>, String> {
+    if target.starts_with('$') {
+        return match &response.json {
+            Some(json) => json_path::select(json, target),
+            None => Ok(None),
+        };
+    }
+
+    let header_name = target
+        .strip_prefix("header.")
+        .or_else(|| target.strip_prefix("headers."))
+        .or_else(|| target.strip_prefix("header:"));
+    if let Some(name) = header_name {
+        return Ok(response
+            .headers
+            .iter()
+            .find(|(header, _)| header.eq_ignore_ascii_case(name))
+            .map(|(_, value)| Value::String(value.clone())));
+    }
+
+    match target {
+        "status" => Ok(Some(Value::from(response.status))),
+        "response_time" | "duration" => Ok(Some(Value::from(response.duration.as_secs_f64() * 1000.0))),
+        "body" => Ok(Some(Value::String(response.body.clone()))),
+        _ => Err(format!("Unknown assertion target: {}", target)),
+    }
+}
+
+fn as_number(value: &Value) -> Option<f64> {
+    match value {
+        Value::Number(number) => number.as_f64(),
+        Value::String(text) => text.trim().parse().ok(),
+        _ => None,
+    }
+}
+
+fn values_equal(actual: &Value, expected: &Value) -> bool {
+    match (as_number(actual), as_number(expected)) {
+        (Some(a), Some(b)) if actual.is_number() || expected.is_number() => a == b,
+        _ if actual.is_string() || expected.is_string() => value_to_text(actual) == value_to_text(expected),
+        _ => actual == expected,
+    }
+}
+
+// Apply an operator to the resolved value, returning a failure message when it does not hold
+fn check(op: AssertionOp, actual: Option<&Value>, expected: Option<&Value>) -> Result<(), String> {
+    if op == AssertionOp::Exists {
+        let should_exist = expected.and_then(Value::as_bool).unwrap_or(true);
+        return match (actual.is_some(), should_exist) {
+            (true, true) | (false, false) => Ok(()),
+            (false, true) => Err("value does not exist".to_string()),
+            (true, false) => Err("value exists".to_string()),
+        };
+    }
+
+    let actual = actual.ok_or_else(|| "value does not exist".to_string())?;
+    let expected = expected.ok_or_else(|| format!("operator {} requires a value", op))?;
+
+    let holds = match op {
+        AssertionOp::Eq => values_equal(actual, expected),
+        AssertionOp::Ne => !values_equal(actual, expected),
+        AssertionOp::Gt | AssertionOp::Ge | AssertionOp::Lt | AssertionOp::Le => {
+            let (a, b) = match (as_number(actual), as_number(expected)) {
+                (Some(a), Some(b)) => (a, b),
+                _ => return Err("values are not numbers".to_string()),
+            };
+            match op {
+                AssertionOp::Gt => a > b,
+                AssertionOp::Ge => a >= b,
+                AssertionOp::Lt => a < b,
+                _ => a <= b,
+            }
+        }
+        AssertionOp::Contains => match actual {
+            Value::String(text) => text.contains(&value_to_text(expected)),
+            Value::Array(items) => items.iter().any(|item| values_equal(item, expected)),
+            Value::Object(map) => map.contains_key(&value_to_text(expected)),
+            _ => return Err(format!("cannot check containment in {}", value_type(actual))),
+        },
+        AssertionOp::Matches => {
+            let regex = Regex::new(&value_to_text(expected)).map_err(|e| format!("invalid regex: {}", e))?;
+            regex.is_match(&value_to_text(actual))
+        }
+        AssertionOp::Type => {
+            let expected_type = value_to_text(expected).to_lowercase();
+            match expected_type.as_str() {
+                "integer" => actual.is_i64() || actual.is_u64(),
+                "bool" => actual.is_boolean(),
+                _ => value_type(actual) == expected_type,
+            }
+        }
+        AssertionOp::Exists => unreachable!(),
+    };
+
+    if holds {
+        Ok(())
+    } else {
+        Err(format!("expected {} {}", op, expected))
+    }
+}
+
+/// Evaluate a single assertion against a response
+pub fn evaluate_assertion(assertion: &Assertion, response: &ApiRequestResult) -> AssertionResult {
+    let (actual, outcome) = match resolve_target(&assertion.target, response) {
+        Ok(actual) => {
+            let outcome = check(assertion.op, actual.as_ref(), assertion.value.as_ref());
+            (actual, outcome)
+        }
+        Err(e) => (None, Err(e)),
+    };
+
+    AssertionResult {
+        assertion: assertion.to_string(),
+        passed: outcome.is_ok(),
+        actual,
+        message: outcome.err(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::collections::HashMap;
+    use std::time::Duration;
+
+    fn response() -> ApiRequestResult {
+        let json = json!({"data": [{"id": 1}], "items": [1, 2], "email": "a@example.com"});
+        ApiRequestResult {
+            duration: Duration::from_millis(120),
+            status: 200,
+            body: json.to_string(),
+            json: Some(json),
+            headers: HashMap::from([("content-type".to_string(), "application/json".to_string())]),
+        }
+    }
+
+    fn passes(expression: &str) -> bool {
+        let assertion: Assertion = expression.parse().unwrap();
+        evaluate_assertion(&assertion, &response()).passed
+    }
+
+    #[test]
+    fn test_parse_expression() {
+        let assertion: Assertion = "response_time < 200ms".parse().unwrap();
+        assert_eq!(assertion.target, "response_time");
+        assert_eq!(assertion.op, AssertionOp::Lt);
+        assert_eq!(assertion.value, Some(json!(200.0)));
+
+        let assertion: Assertion = r#"$.email matches ".*@.*""#.parse().unwrap();
+        assert_eq!(assertion.value, Some(json!(".*@.*")));
+
+        assert!("$.id".parse::<Assertion>().is_err());
+        assert!("$.id == ".parse::<Assertion>().is_err());
+        assert!("$.id exists".parse::<Assertion>().is_ok());
+    }
+
+    #[test]
+    fn test_deserialize_both_forms() {
+        let assertions: Vec<Assertion> = serde_json::from_value(json!([
+            "$.data[0].id == 1",
+            {"target": "response_time", "op": "lt", "value": "1s"}
+        ])).unwrap();
+        assert_eq!(assertions[0].op, AssertionOp::Eq);
+        assert_eq!(assertions[1].value, Some(json!(1000.0)));
+
+        // Only duration targets read "30s" as milliseconds
+        let ttl: Assertion = serde_json::from_value(json!({"target": "$.ttl", "op": "eq", "value": "30s"})).unwrap();
+        assert_eq!(ttl.value, Some(json!("30s")));
+        let ttl: Assertion = "$.ttl == 30s".parse().unwrap();
+        assert_eq!(ttl.value, Some(json!("30s")));
+        let ttl = json!({"ttl": "30s"});
+        let response = ApiRequestResult { json: Some(ttl), ..response() };
+        assert!(evaluate_assertion(&"$.ttl == 30s".parse().unwrap(), &response).passed);
+    }
+
+    #[test]
+    fn test_evaluate_assertions() {
+        assert!(passes("$.data[0].id == 1"));
+        assert!(passes("$.items.length > 0"));
+        assert!(passes(r#"$.email matches ".*@.*""#));
+        assert!(passes("$.items type array"));
+        assert!(passes("$.data[0].id type integer"));
+        assert!(passes("$.missing exists false"));
+        assert!(passes("header.Content-Type contains json"));
+        assert!(passes("status == 200"));
+        assert!(passes("response_time < 200ms"));
+
+        assert!(!passes("$.data[0].id != 1"));
+        assert!(!passes("$.missing exists"));
+        assert!(!passes("$.email > 3"));
+
+        let result = evaluate_assertion(&"$.items.length > 5".parse().unwrap(), &response());
+        assert_eq!(result.actual, Some(json!(2)));
+        assert_eq!(result.message.as_deref(), Some("expected > 5"));
+    }
+}
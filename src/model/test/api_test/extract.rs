@@ -0,0 +1,148 @@
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+
+use crate::model::test::ApiRequestResult;
+use crate::model::utils::json_path;
+
+/// Where an extracted value is read from
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ExtractSource {
+    /// JSON path into the response body, e.g. `$.data.token`
+    JsonPath(String),
+    /// Response header name, matched case-insensitively
+    Header(String),
+    /// Regex over the raw body; the first capture group is used when present
+    Regex(ExtractPattern),
+}
+
+/// A regular expression compiled when its rule is parsed, written as its source text
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct ExtractPattern(Regex);
+
+impl TryFrom<String> for ExtractPattern {
+    type Error = String;
+
+    fn try_from(pattern: String) -> Result<Self, Self::Error> {
+        Regex::new(&pattern).map(ExtractPattern).map_err(|e| format!("Invalid extract regex: {}", e))
+    }
+}
+
+impl From<ExtractPattern> for String {
+    fn from(pattern: ExtractPattern) -> Self {
+        pattern.0.as_str().to_string()
+    }
+}
+
+impl PartialEq for ExtractPattern {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.as_str() == other.0.as_str()
+    }
+}
+
+/// Rule storing a value from a response into the run's variables
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExtractRule {
+    /// Variable name, referenced later as `{{name}}`
+    pub name: String,
+    #[serde(flatten)]
+    pub source: ExtractSource,
+}
+
+// Resolve a single extraction rule against a response
+fn extract_value(source: &ExtractSource, response: &ApiRequestResult) -> Result<Value, String> {
+    match source {
+        ExtractSource::JsonPath(path) => {
+            let json = response
+                .json
+                .as_ref()
+                .ok_or_else(|| "response body is not valid JSON".to_string())?;
+            json_path::select(json, path)?.ok_or_else(|| format!("no value at {}", path))
+        }
+        ExtractSource::Header(name) => response
+            .headers
+            .iter()
+            .find(|(header, _)| header.eq_ignore_ascii_case(name))
+            .map(|(_, value)| Value::String(value.clone()))
+            .ok_or_else(|| format!("missing header {}", name)),
+        ExtractSource::Regex(ExtractPattern(regex)) => {
+            let captures = regex
+                .captures(&response.body)
+                .ok_or_else(|| format!("no match for /{}/", regex.as_str()))?;
+            let matched = captures.get(1).or_else(|| captures.get(0)).map(|m| m.as_str()).unwrap_or_default();
+            Ok(Value::String(matched.to_string()))
+        }
+    }
+}
+
+/// Apply extraction rules to a response, returning the extracted variables and
+/// a message for every rule that could not be resolved
+pub fn extract_variables(rules: &[ExtractRule], response: &ApiRequestResult) -> (HashMap<String, Value>, Vec<String>) {
+    let mut variables = HashMap::new();
+    let mut errors = Vec::new();
+
+    for rule in rules {
+        match extract_value(&rule.source, response) {
+            Ok(value) => {
+                variables.insert(rule.name.clone(), value);
+            }
+            Err(e) => errors.push(format!("Failed to extract '{}': {}", rule.name, e)),
+        }
+    }
+
+    (variables, errors)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::time::Duration;
+
+    fn rules(value: Value) -> Vec<ExtractRule> {
+        serde_json::from_value(value).unwrap()
+    }
+
+    #[test]
+    fn test_extract_variables() {
+        let body = r#"{"data": {"token": "abc"}, "csrf": "<input name=csrf value=x9>"}"#;
+        let response = ApiRequestResult {
+            status: 200,
+            duration: Duration::from_millis(5),
+            headers: HashMap::from([("Location".to_string(), "/users/7".to_string())]),
+            json: serde_json::from_str(body).ok(),
+            body: body.to_string(),
+        };
+        let rules = rules(json!([
+            {"name": "token", "json_path": "$.data.token"},
+            {"name": "location", "header": "location"},
+            {"name": "csrf", "regex": "value=(\\w+)"},
+            {"name": "missing", "json_path": "$.data.id"},
+            {"name": "etag", "header": "ETag"},
+            {"name": "order", "regex": "order-\\d+"}
+        ]));
+
+        let (variables, errors) = extract_variables(&rules, &response);
+        assert_eq!(variables.len(), 3);
+        assert_eq!(variables["token"], json!("abc"));
+        assert_eq!(variables["location"], json!("/users/7"));
+        assert_eq!(variables["csrf"], json!("x9"));
+        assert_eq!(
+            errors,
+            vec![
+                "Failed to extract 'missing': no value at $.data.id",
+                "Failed to extract 'etag': missing header ETag",
+                "Failed to extract 'order': no match for /order-\\d+/",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_invalid_regex_is_rejected_when_parsed() {
+        let error = serde_json::from_value::<ExtractRule>(json!({"name": "id", "regex": "("})).unwrap_err();
+        assert!(error.to_string().contains("Invalid extract regex"), "{}", error);
+    }
+}
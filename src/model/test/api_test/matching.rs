@@ -0,0 +1,197 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+
+/// Which part of the response a mismatch was found in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MismatchKind {
+    Body,
+    Header,
+}
+
+/// A single difference between the expected and the actual response
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Mismatch {
+    pub kind: MismatchKind,
+    /// JSON path of the body value (e.g. `$.data[0].id`) or the header name
+    pub path: String,
+    pub expected: Value,
+    pub actual: Option<Value>,
+    pub reason: String,
+}
+
+pub(crate) fn value_type(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+fn body_mismatch(path: &str, expected: &Value, actual: Option<&Value>, reason: String) -> Mismatch {
+    Mismatch {
+        kind: MismatchKind::Body,
+        path: path.to_string(),
+        expected: expected.clone(),
+        actual: actual.cloned(),
+        reason,
+    }
+}
+
+// Recursively collect the mismatches of `actual` against the `expected` subset
+fn collect_body_mismatches(path: &str, expected: &Value, actual: &Value, mismatches: &mut Vec<Mismatch>) {
+    match (expected, actual) {
+        (Value::Object(expected_map), Value::Object(actual_map)) => {
+            for (key, expected_value) in expected_map {
+                let child_path = format!("{}.{}", path, key);
+                match actual_map.get(key) {
+                    Some(actual_value) => collect_body_mismatches(&child_path, expected_value, actual_value, mismatches),
+                    None => mismatches.push(body_mismatch(&child_path, expected_value, None, "missing key".to_string())),
+                }
+            }
+        }
+        (Value::Array(expected_items), Value::Array(actual_items)) => {
+            if actual_items.len() < expected_items.len() {
+                mismatches.push(body_mismatch(
+                    path,
+                    expected,
+                    Some(actual),
+                    format!("expected at least {} elements, got {}", expected_items.len(), actual_items.len()),
+                ));
+                return;
+            }
+            for (index, (expected_item, actual_item)) in expected_items.iter().zip(actual_items).enumerate() {
+                collect_body_mismatches(&format!("{}[{}]", path, index), expected_item, actual_item, mismatches);
+            }
+        }
+        (Value::Number(expected_number), Value::Number(actual_number)) => {
+            if expected_number.as_f64() != actual_number.as_f64() {
+                mismatches.push(body_mismatch(path, expected, Some(actual), "value differs".to_string()));
+            }
+        }
+        _ if value_type(expected) != value_type(actual) => {
+            mismatches.push(body_mismatch(
+                path,
+                expected,
+                Some(actual),
+                format!("expected {}, got {}", value_type(expected), value_type(actual)),
+            ));
+        }
+        _ => {
+            if expected != actual {
+                mismatches.push(body_mismatch(path, expected, Some(actual), "value differs".to_string()));
+            }
+        }
+    }
+}
+
+/// Compare a response body against an expected subset.
+///
+/// Objects match when every expected key matches, extra keys are ignored.
+/// Arrays match when their first elements match the expected elements.
+/// A string expectation is compared against the raw body when it is not JSON.
+pub fn match_body(expected: &Value, actual_json: Option<&Value>, actual_text: &str) -> Vec<Mismatch> {
+    let mut mismatches = Vec::new();
+    match actual_json {
+        Some(actual) => collect_body_mismatches("$", expected, actual, &mut mismatches),
+        None => match expected {
+            Value::String(expected_text) if expected_text == actual_text => {}
+            Value::String(_) => mismatches.push(body_mismatch(
+                "$",
+                expected,
+                Some(&Value::String(actual_text.to_string())),
+                "value differs".to_string(),
+            )),
+            _ => mismatches.push(body_mismatch("$", expected, None, "response body is not valid JSON".to_string())),
+        },
+    }
+    mismatches
+}
+
+/// Compare response headers against expected values, matching header names case-insensitively
+pub fn match_headers(expected: &HashMap<String, String>, actual: &HashMap<String, String>) -> Vec<Mismatch> {
+    let mut expected: Vec<(&String, &String)> = expected.iter().collect();
+    expected.sort();
+
+    expected
+        .into_iter()
+        .filter_map(|(name, expected_value)| {
+            let actual_value = actual
+                .iter()
+                .find(|(actual_name, _)| actual_name.eq_ignore_ascii_case(name))
+                .map(|(_, value)| value);
+
+            let reason = match actual_value {
+                None => "missing header",
+                Some(value) if value.trim() != expected_value.trim() => "value differs",
+                Some(_) => return None,
+            };
+
+            Some(Mismatch {
+                kind: MismatchKind::Header,
+                path: name.clone(),
+                expected: Value::String(expected_value.clone()),
+                actual: actual_value.map(|value| Value::String(value.clone())),
+                reason: reason.to_string(),
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_match_body_subset() {
+        let actual = json!({"id": 1, "name": "Leanne", "address": {"city": "Gwenborough"}});
+        assert!(match_body(&json!({"id": 1, "address": {"city": "Gwenborough"}}), Some(&actual), "").is_empty());
+
+        let mismatches = match_body(&json!({"id": 2, "email": "a@b.c"}), Some(&actual), "");
+        assert_eq!(mismatches.len(), 2);
+        assert_eq!(mismatches[0].path, "$.email");
+        assert_eq!(mismatches[0].reason, "missing key");
+        assert_eq!(mismatches[1].path, "$.id");
+        assert_eq!(mismatches[1].actual, Some(json!(1)));
+    }
+
+    #[test]
+    fn test_match_body_array_prefix() {
+        let actual = json!([{"id": 1}, {"id": 2}, {"id": 3}]);
+        assert!(match_body(&json!([{"id": 1}]), Some(&actual), "").is_empty());
+
+        let mismatches = match_body(&json!([{"id": 1}, {"id": 3}]), Some(&actual), "");
+        assert_eq!(mismatches.len(), 1);
+        assert_eq!(mismatches[0].path, "$[1].id");
+
+        let mismatches = match_body(&json!([1, 2, 3, 4]), Some(&json!([1])), "");
+        assert_eq!(mismatches[0].reason, "expected at least 4 elements, got 1");
+    }
+
+    #[test]
+    fn test_match_body_non_json() {
+        assert!(match_body(&json!("pong"), None, "pong").is_empty());
+        assert_eq!(match_body(&json!({"id": 1}), None, "<html>")[0].reason, "response body is not valid JSON");
+    }
+
+    #[test]
+    fn test_match_headers_case_insensitive() {
+        let actual = HashMap::from([("access-control-allow-methods".to_string(), "GET,POST".to_string())]);
+        let expected = HashMap::from([("Access-Control-Allow-Methods".to_string(), "GET,POST".to_string())]);
+        assert!(match_headers(&expected, &actual).is_empty());
+
+        let expected = HashMap::from([
+            ("Access-Control-Allow-Methods".to_string(), "GET".to_string()),
+            ("X-Request-Id".to_string(), "abc".to_string()),
+        ]);
+        let mismatches = match_headers(&expected, &actual);
+        assert_eq!(mismatches.len(), 2);
+        assert_eq!(mismatches[0].reason, "value differs");
+        assert_eq!(mismatches[1].reason, "missing header");
+    }
+}
@@ -0,0 +1,517 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+use std::collections::HashMap;
+
+use crate::model::error::AppError;
+use crate::model::test::{FormBody, RequestBody, RequestTemplate, WeightedEndpoint};
+use crate::model::test::api_test::{create_api_test, ApiTest, ApiTestConfig, SchemaSource};
+use crate::model::utils::template::value_to_text;
+
+const HTTP_METHODS: [&str; 7] = ["get", "put", "post", "delete", "options", "head", "patch"];
+const SAFE_METHODS: [&str; 3] = ["get", "head", "options"];
+
+/// Operation extension overriding the weight of an operation in the generated load scenario
+pub const WEIGHT_EXTENSION: &str = "x-ballista-weight";
+
+/// Options for importing an OpenAPI document
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct OpenApiImportOptions {
+    /// Base URL to use instead of the first `servers` entry
+    #[serde(default)]
+    pub base_url: Option<String>,
+    /// Also generate a weighted multi-endpoint load scenario
+    #[serde(default)]
+    pub load_scenario: bool,
+}
+
+/// Result of importing an OpenAPI document
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OpenApiImport {
+    pub api_tests: ApiTestConfig,
+    pub load_scenario: Option<Vec<WeightedEndpoint>>,
+}
+
+/// Parse a JSON or YAML document into a JSON value
+pub fn parse_document(content: &str) -> Result<Value, AppError> {
+    if let Ok(value) = serde_json::from_str::<Value>(content) {
+        return Ok(value);
+    }
+    let yaml: serde_yaml::Value = serde_yaml::from_str(content)
+        .map_err(|e| AppError::InvalidConfig(format!("Document is neither valid JSON nor YAML: {}", e)))?;
+    Ok(yaml_to_json(yaml))
+}
+
+// Convert YAML to JSON, turning non-string mapping keys (e.g. `200:`) into strings
+fn yaml_to_json(value: serde_yaml::Value) -> Value {
+    match value {
+        serde_yaml::Value::Null => Value::Null,
+        serde_yaml::Value::Bool(b) => Value::Bool(b),
+        serde_yaml::Value::Number(n) => {
+            if let Some(i) = n.as_i64() {
+                Value::from(i)
+            } else if let Some(u) = n.as_u64() {
+                Value::from(u)
+            } else {
+                n.as_f64().map(Value::from).unwrap_or(Value::Null)
+            }
+        }
+        serde_yaml::Value::String(s) => Value::String(s),
+        serde_yaml::Value::Sequence(items) => Value::Array(items.into_iter().map(yaml_to_json).collect()),
+        serde_yaml::Value::Mapping(mapping) => Value::Object(
+            mapping
+                .into_iter()
+                .map(|(key, value)| {
+                    let key = match key {
+                        serde_yaml::Value::String(s) => s,
+                        other => value_to_text(&yaml_to_json(other)),
+                    };
+                    (key, yaml_to_json(value))
+                })
+                .collect(),
+        ),
+        serde_yaml::Value::Tagged(tagged) => yaml_to_json(tagged.value),
+    }
+}
+
+// Look up a local `#/...` reference
+fn lookup_ref<'a>(spec: &'a Value, reference: &str) -> Option<&'a Value> {
+    reference.strip_prefix('#').and_then(|pointer| spec.pointer(pointer))
+}
+
+// Follow `$ref`s until a concrete object is reached
+fn resolve<'a>(spec: &'a Value, value: &'a Value) -> &'a Value {
+    let mut current = value;
+    for _ in 0..32 {
+        match current.get("$ref").and_then(Value::as_str).and_then(|r| lookup_ref(spec, r)) {
+            Some(target) => current = target,
+            None => break,
+        }
+    }
+    current
+}
+
+// Inline every `$ref` of a schema and convert OpenAPI 3.0 `nullable`; recursive references become `{}`
+fn inline_schema(spec: &Value, schema: &Value, stack: &mut Vec<String>) -> Value {
+    match schema {
+        Value::Object(map) => {
+            if let Some(reference) = map.get("$ref").and_then(Value::as_str) {
+                if stack.iter().any(|r| r == reference) {
+                    return Value::Object(Map::new());
+                }
+                return match lookup_ref(spec, reference) {
+                    Some(target) => {
+                        stack.push(reference.to_string());
+                        let inlined = inline_schema(spec, target, stack);
+                        stack.pop();
+                        inlined
+                    }
+                    None => schema.clone(),
+                };
+            }
+
+            let mut inlined: Map<String, Value> = map
+                .iter()
+                .map(|(key, value)| (key.clone(), inline_schema(spec, value, stack)))
+                .collect();
+            if inlined.remove("nullable") == Some(Value::Bool(true)) {
+                if let Some(Value::String(schema_type)) = inlined.get("type").cloned() {
+                    inlined.insert("type".to_string(), json!([schema_type, "null"]));
+                }
+            }
+            Value::Object(inlined)
+        }
+        Value::Array(items) => Value::Array(items.iter().map(|item| inline_schema(spec, item, stack)).collect()),
+        other => other.clone(),
+    }
+}
+
+/// Generate an example value from an (inlined) schema
+pub fn example_from_schema(schema: &Value) -> Value {
+    if let Some(example) = schema.get("example") {
+        return example.clone();
+    }
+    if let Some(example) = schema.get("examples").and_then(Value::as_array).and_then(|e| e.first()) {
+        return example.clone();
+    }
+    if let Some(default) = schema.get("default") {
+        return default.clone();
+    }
+    if let Some(first) = schema.get("enum").and_then(Value::as_array).and_then(|e| e.first()) {
+        return first.clone();
+    }
+    if let Some(parts) = schema.get("allOf").and_then(Value::as_array) {
+        let mut merged = Map::new();
+        for part in parts {
+            if let Value::Object(map) = example_from_schema(part) {
+                merged.extend(map);
+            }
+        }
+        return Value::Object(merged);
+    }
+    if let Some(first) = ["oneOf", "anyOf"]
+        .iter()
+        .find_map(|key| schema.get(*key).and_then(Value::as_array).and_then(|e| e.first()))
+    {
+        return example_from_schema(first);
+    }
+
+    let schema_type = match schema.get("type") {
+        Some(Value::String(t)) => t.as_str(),
+        Some(Value::Array(types)) => types.iter().filter_map(Value::as_str).find(|t| *t != "null").unwrap_or("null"),
+        _ if schema.get("properties").is_some() => "object",
+        _ => "",
+    };
+
+    match schema_type {
+        "object" => Value::Object(
+            schema
+                .get("properties")
+                .and_then(Value::as_object)
+                .map(|properties| {
+                    properties
+                        .iter()
+                        .map(|(name, property)| (name.clone(), example_from_schema(property)))
+                        .collect()
+                })
+                .unwrap_or_default(),
+        ),
+        "array" => match schema.get("items") {
+            Some(items) => json!([example_from_schema(items)]),
+            None => json!([]),
+        },
+        "string" => Value::String(
+            match schema.get("format").and_then(Value::as_str) {
+                Some("date-time") => "2024-01-01T00:00:00Z",
+                Some("date") => "2024-01-01",
+                Some("email") => "user@example.com",
+                Some("uuid") => "00000000-0000-0000-0000-000000000000",
+                Some("uri") | Some("url") => "https://example.com",
+                _ => "string",
+            }
+            .to_string(),
+        ),
+        "integer" => schema.get("minimum").cloned().unwrap_or(json!(1)),
+        "number" => schema.get("minimum").cloned().unwrap_or(json!(1.0)),
+        "boolean" => Value::Bool(true),
+        _ => Value::Null,
+    }
+}
+
+// Example of a parameter or media type object, falling back to its schema
+fn example_of(spec: &Value, object: &Value) -> Value {
+    if let Some(example) = object.get("example") {
+        return example.clone();
+    }
+    let first_example = object
+        .get("examples")
+        .and_then(Value::as_object)
+        .and_then(|examples| examples.values().next())
+        .map(|example| resolve(spec, example));
+    if let Some(value) = first_example.and_then(|example| example.get("value")) {
+        return value.clone();
+    }
+    object
+        .get("schema")
+        .map(|schema| example_from_schema(&inline_schema(spec, schema, &mut Vec::new())))
+        .unwrap_or(Value::Null)
+}
+
+// Base URL from the first server entry, with server variables replaced by their defaults
+fn server_url(spec: &Value) -> String {
+    let Some(server) = spec.get("servers").and_then(Value::as_array).and_then(|s| s.first()) else {
+        return String::new();
+    };
+    let mut url = server.get("url").and_then(Value::as_str).unwrap_or_default().to_string();
+    if let Some(variables) = server.get("variables").and_then(Value::as_object) {
+        for (name, variable) in variables {
+            let default = variable.get("default").map(value_to_text).unwrap_or_default();
+            url = url.replace(&format!("{{{}}}", name), &default);
+        }
+    }
+    url
+}
+
+// Pick the documented success status, preferring the lowest 2xx code
+fn expected_status(responses: Option<&Map<String, Value>>) -> (u16, Option<String>) {
+    let Some(responses) = responses else {
+        return (200, None);
+    };
+
+    let mut success: Vec<(u16, &String)> = responses
+        .keys()
+        .filter_map(|code| match code.to_uppercase().as_str() {
+            "2XX" => Some((200, code)),
+            other => other.parse::<u16>().ok().filter(|c| (200..300).contains(c)).map(|c| (c, code)),
+        })
+        .collect();
+    success.sort();
+
+    if let Some((status, key)) = success.first() {
+        return (*status, Some((*key).clone()));
+    }
+    if responses.contains_key("default") {
+        return (200, Some("default".to_string()));
+    }
+    (200, None)
+}
+
+// Choose the JSON media type of a content map, if any
+fn json_media_type(content: &Map<String, Value>) -> Option<(&String, &Value)> {
+    content
+        .iter()
+        .find(|(media_type, _)| media_type.as_str() == "application/json")
+        .or_else(|| content.iter().find(|(media_type, _)| media_type.contains("json")))
+}
+
+fn build_operation(
+    spec: &Value,
+    base_url: &str,
+    path: &str,
+    method: &str,
+    path_item: &Value,
+    operation: &Value,
+) -> ApiTest {
+    let name = operation
+        .get("operationId")
+        .or_else(|| operation.get("summary"))
+        .and_then(Value::as_str)
+        .map(str::to_string)
+        .unwrap_or_else(|| format!("{} {}", method.to_uppercase(), path));
+
+    // Operation parameters override path-level parameters with the same name and location
+    let mut parameters: Vec<&Value> = Vec::new();
+    for parameter in path_item
+        .get("parameters")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .chain(operation.get("parameters").and_then(Value::as_array).into_iter().flatten())
+    {
+        let parameter = resolve(spec, parameter);
+        let key = (parameter.get("name"), parameter.get("in"));
+        parameters.retain(|existing| (existing.get("name"), existing.get("in")) != key);
+        parameters.push(parameter);
+    }
+
+    let mut resolved_path = path.to_string();
+    let mut query: Vec<(String, String)> = Vec::new();
+    let mut headers: HashMap<String, String> = HashMap::new();
+
+    for parameter in parameters {
+        let Some(param_name) = parameter.get("name").and_then(Value::as_str) else {
+            continue;
+        };
+        let required = parameter.get("required").and_then(Value::as_bool).unwrap_or(false);
+        let value = value_to_text(&example_of(spec, parameter));
+        match parameter.get("in").and_then(Value::as_str) {
+            Some("path") => resolved_path = resolved_path.replace(&format!("{{{}}}", param_name), &value),
+            Some("query") if required => query.push((param_name.to_string(), value)),
+            Some("header") if required => {
+                headers.insert(param_name.to_string(), value);
+            }
+            _ => {}
+        }
+    }
+
+    let mut url = format!("{}{}", base_url.trim_end_matches('/'), resolved_path);
+    if !query.is_empty() {
+        let encoded = url::form_urlencoded::Serializer::new(String::new())
+            .extend_pairs(query)
+            .finish();
+        url = format!("{}?{}", url, encoded);
+    }
+
+    let body = operation
+        .get("requestBody")
+        .map(|request_body| resolve(spec, request_body))
+        .and_then(|request_body| request_body.get("content").and_then(Value::as_object))
+        .and_then(|content| {
+            if let Some((_, media)) = json_media_type(content) {
+                return Some(RequestBody::from_json(example_of(spec, media)));
+            }
+            if let Some(media) = content.get("application/x-www-form-urlencoded") {
+                let form = match example_of(spec, media) {
+                    Value::Object(fields) => fields.iter().map(|(k, v)| (k.clone(), value_to_text(v))).collect(),
+                    _ => HashMap::new(),
+                };
+                return Some(RequestBody::Form(FormBody { form }));
+            }
+            content.iter().next().map(|(media_type, media)| {
+                headers.insert("Content-Type".to_string(), media_type.clone());
+                RequestBody::Text(value_to_text(&example_of(spec, media)))
+            })
+        });
+
+    let responses = operation.get("responses").and_then(Value::as_object);
+    let (status, response_key) = expected_status(responses);
+    let schema = response_key
+        .and_then(|key| responses.and_then(|r| r.get(&key)))
+        .map(|response| resolve(spec, response))
+        .and_then(|response| response.get("content").and_then(Value::as_object))
+        .and_then(json_media_type)
+        .and_then(|(_, media)| media.get("schema"))
+        .map(|schema| SchemaSource::Inline(inline_schema(spec, schema, &mut Vec::new())));
+
+    let mut test = create_api_test(name, method.to_uppercase(), url, status);
+    test.headers = if headers.is_empty() { None } else { Some(headers) };
+    test.body = body;
+    test.schema = schema;
+    test
+}
+
+// Weight of an operation in the load scenario; unsafe methods are skipped unless weighted explicitly
+fn scenario_weight(method: &str, operation: &Value) -> u32 {
+    match operation.get(WEIGHT_EXTENSION).and_then(Value::as_u64) {
+        Some(weight) => weight.min(u32::MAX as u64) as u32,
+        None if SAFE_METHODS.contains(&method) => 1,
+        None => 0,
+    }
+}
+
+/// Generate an API test suite, and optionally a weighted load scenario, from an OpenAPI 3.x document.
+///
+/// Each operation becomes one `ApiTest` using documented examples (or values generated from
+/// schemas) for parameters and bodies, the lowest documented 2xx status and the JSON schema
+/// of that response. The load scenario includes GET/HEAD/OPTIONS operations with weight 1 and
+/// any operation that sets `x-ballista-weight`.
+pub fn import_openapi(spec: &Value, options: &OpenApiImportOptions) -> Result<OpenApiImport, AppError> {
+    let version = spec.get("openapi").and_then(Value::as_str).unwrap_or_default();
+    if !version.starts_with("3.") {
+        return Err(AppError::InvalidConfig(format!(
+            "Unsupported OpenAPI version '{}', expected 3.x",
+            version
+        )));
+    }
+
+    let paths = spec
+        .get("paths")
+        .and_then(Value::as_object)
+        .ok_or_else(|| AppError::InvalidConfig("OpenAPI document has no paths".to_string()))?;
+
+    let base_url = options.base_url.clone().unwrap_or_else(|| server_url(spec));
+    let mut tests = Vec::new();
+    let mut scenario = Vec::new();
+
+    for (path, path_item) in paths {
+        let path_item = resolve(spec, path_item);
+        for method in HTTP_METHODS {
+            let Some(operation) = path_item.get(method) else {
+                continue;
+            };
+            let test = build_operation(spec, &base_url, path, method, path_item, operation);
+
+            let weight = scenario_weight(method, operation);
+            if options.load_scenario && weight > 0 {
+                scenario.push(WeightedEndpoint {
+                    name: test.name.clone(),
+                    weight,
+                    request: RequestTemplate {
+                        method: test.method.clone(),
+                        url: test.url.clone(),
+                        headers: test.headers.clone(),
+                        body: test.body.clone(),
+                        query_params: None,
+                        think_time_ms: None,
+                        extract: None,
+                    },
+                });
+            }
+            tests.push(test);
+        }
+    }
+
+    if tests.is_empty() {
+        return Err(AppError::InvalidConfig("OpenAPI document has no operations".to_string()));
+    }
+
+    Ok(OpenApiImport {
+        api_tests: ApiTestConfig { tests },
+        load_scenario: if options.load_scenario { Some(scenario) } else { None },
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SPEC: &str = r#"
+openapi: 3.0.3
+servers:
+  - url: https://{env}.example.com/v1
+    variables:
+      env:
+        default: api
+paths:
+  /users/{id}:
+    parameters:
+      - name: id
+        in: path
+        required: true
+        schema: { type: integer, example: 7 }
+    get:
+      operationId: getUser
+      responses:
+        200:
+          description: ok
+          content:
+            application/json:
+              schema: { $ref: '#/components/schemas/User' }
+        404:
+          description: missing
+  /users:
+    post:
+      x-ballista-weight: 2
+      requestBody:
+        content:
+          application/json:
+            schema: { $ref: '#/components/schemas/User' }
+      responses:
+        '201':
+          description: created
+components:
+  schemas:
+    User:
+      type: object
+      required: [id]
+      properties:
+        id: { type: integer }
+        email: { type: string, format: email, nullable: true }
+        manager: { $ref: '#/components/schemas/User' }
+"#;
+
+    #[test]
+    fn test_import_openapi() {
+        let spec = parse_document(SPEC).unwrap();
+        let options = OpenApiImportOptions { base_url: None, load_scenario: true };
+        let import = import_openapi(&spec, &options).unwrap();
+        let tests = &import.api_tests.tests;
+
+        assert_eq!(tests.len(), 2);
+        let create = tests.iter().find(|t| t.method == "POST").unwrap();
+        assert_eq!(create.url, "https://api.example.com/v1/users");
+        assert_eq!(create.expected_status, 201);
+        assert!(matches!(
+            &create.body,
+            Some(RequestBody::Json(body)) if body["email"] == "user@example.com" && body["manager"].is_null()
+        ));
+
+        let get = tests.iter().find(|t| t.name == "getUser").unwrap();
+        assert_eq!(get.url, "https://api.example.com/v1/users/7");
+        assert_eq!(get.expected_status, 200);
+        let Some(SchemaSource::Inline(schema)) = &get.schema else {
+            panic!("expected an inline schema");
+        };
+        assert_eq!(schema["properties"]["email"]["type"], json!(["string", "null"]));
+        assert_eq!(schema["properties"]["manager"], json!({}));
+
+        let scenario = import.load_scenario.unwrap();
+        assert_eq!(scenario.len(), 2);
+        assert_eq!(scenario.iter().find(|e| e.request.method == "POST").unwrap().weight, 2);
+    }
+
+    #[test]
+    fn test_import_rejects_swagger_2() {
+        let spec = json!({"swagger": "2.0", "paths": {}});
+        assert!(import_openapi(&spec, &OpenApiImportOptions::default()).is_err());
+    }
+}
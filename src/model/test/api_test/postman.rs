@@ -0,0 +1,365 @@
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+use regex::Regex;
+use serde_json::Value;
+use std::collections::HashMap;
+use url::form_urlencoded;
+
+use crate::model::error::AppError;
+use crate::model::test::{FileBody, FormBody, RequestBody};
+use crate::model::test::api_test::{create_api_test, ApiTest, ApiTestConfig};
+use crate::model::utils::template::{interpolate, interpolate_map, value_to_text};
+
+// Patterns of `pm.test` scripts that check the response status
+const STATUS_PATTERNS: [&str; 3] = [
+    r"to\.have\.status\(\s*(\d{3})\s*\)",
+    r"response\.code\s*\)\s*\.to\.(?:equal|eql|be)\(\s*(\d{3})\s*\)",
+    r"responseCode\.code\s*===?\s*(\d{3})",
+];
+
+fn is_enabled(entry: &Value) -> bool {
+    !entry.get("disabled").and_then(Value::as_bool).unwrap_or(false)
+        && entry.get("enabled").and_then(Value::as_bool).unwrap_or(true)
+}
+
+// Collect `{key, value}` entries into a map, skipping disabled ones
+fn key_values(entries: Option<&Value>) -> HashMap<String, String> {
+    entries
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter(|entry| is_enabled(entry))
+        .filter_map(|entry| {
+            let key = entry.get("key").and_then(Value::as_str)?;
+            let value = entry.get("value").map(value_to_text).unwrap_or_default();
+            Some((key.to_string(), value))
+        })
+        .collect()
+}
+
+// Variables from the collection, overridden by the environment
+fn collect_variables(collection: &Value, environment: Option<&Value>) -> HashMap<String, Value> {
+    let mut variables: HashMap<String, Value> = key_values(collection.get("variable"))
+        .into_iter()
+        .map(|(key, value)| (key, Value::String(value)))
+        .collect();
+    if let Some(environment) = environment {
+        variables.extend(
+            key_values(environment.get("values"))
+                .into_iter()
+                .map(|(key, value)| (key, Value::String(value))),
+        );
+    }
+    variables
+}
+
+// Percent-encode a query key or value, leaving `{{name}}` placeholders for later interpolation
+fn encode_query_part(text: &str) -> String {
+    let encode = |part: &str| form_urlencoded::byte_serialize(part.as_bytes()).collect::<String>();
+    let mut encoded = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some((start, end)) = rest
+        .find("{{")
+        .and_then(|start| rest[start..].find("}}").map(|end| (start, start + end + 2)))
+    {
+        encoded.push_str(&encode(&rest[..start]));
+        encoded.push_str(&rest[start..end]);
+        rest = &rest[end..];
+    }
+    encoded.push_str(&encode(rest));
+    encoded
+}
+
+// Postman URLs are either a string or an object with `raw` or its parts; query parameters given
+// as parts are filled with the known variables and percent-encoded
+fn request_url(url: Option<&Value>, variables: &HashMap<String, Value>) -> String {
+    match url {
+        Some(Value::String(raw)) => raw.clone(),
+        Some(url) => {
+            if let Some(raw) = url.get("raw").and_then(Value::as_str) {
+                return raw.to_string();
+            }
+            let join = |key: &str, separator: &str| match url.get(key) {
+                Some(Value::Array(parts)) => parts.iter().map(value_to_text).collect::<Vec<_>>().join(separator),
+                Some(Value::String(part)) => part.clone(),
+                _ => String::new(),
+            };
+            let mut built = join("host", ".");
+            if let Some(protocol) = url.get("protocol").and_then(Value::as_str) {
+                built = format!("{}://{}", protocol, built);
+            }
+            let path = join("path", "/");
+            if !path.is_empty() {
+                built = format!("{}/{}", built, path);
+            }
+            let query = key_values(url.get("query"));
+            if !query.is_empty() {
+                let mut pairs: Vec<_> = query.into_iter().collect();
+                pairs.sort();
+                let query = pairs
+                    .iter()
+                    .map(|(k, v)| {
+                        let (k, v) = (interpolate(k, variables), interpolate(v, variables));
+                        format!("{}={}", encode_query_part(&k), encode_query_part(&v))
+                    })
+                    .collect::<Vec<_>>()
+                    .join("&");
+                built = format!("{}?{}", built, query);
+            }
+            built
+        }
+        None => String::new(),
+    }
+}
+
+// Map Postman auth settings to an Authorization header
+fn auth_header(auth: Option<&Value>) -> Option<String> {
+    let auth = auth?;
+    let setting = |kind: &str, key: &str| {
+        auth.get(kind)
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+            .find(|entry| entry.get("key").and_then(Value::as_str) == Some(key))
+            .and_then(|entry| entry.get("value"))
+            .map(value_to_text)
+    };
+
+    match auth.get("type").and_then(Value::as_str) {
+        Some("bearer") => setting("bearer", "token").map(|token| format!("Bearer {}", token)),
+        Some("basic") => {
+            let username = setting("basic", "username").unwrap_or_default();
+            let password = setting("basic", "password").unwrap_or_default();
+            Some(format!("Basic {}", STANDARD.encode(format!("{}:{}", username, password))))
+        }
+        _ => None,
+    }
+}
+
+fn request_body(name: &str, body: Option<&Value>, headers: &HashMap<String, String>) -> Result<Option<RequestBody>, AppError> {
+    let Some(body) = body else {
+        return Ok(None);
+    };
+    let Some(mode) = body.get("mode").and_then(Value::as_str) else {
+        return Ok(None);
+    };
+    let body = match mode {
+        "raw" => {
+            let raw = body.get("raw").and_then(Value::as_str).unwrap_or_default();
+            if raw.is_empty() {
+                return Ok(None);
+            }
+            let is_json = body.pointer("/options/raw/language").and_then(Value::as_str) == Some("json")
+                || headers.iter().any(|(k, v)| k.eq_ignore_ascii_case("content-type") && v.contains("json"));
+            match serde_json::from_str::<Value>(raw) {
+                Ok(value) if is_json => Some(RequestBody::from_json(value)),
+                _ => Some(RequestBody::Text(raw.to_string())),
+            }
+        }
+        "urlencoded" => Some(RequestBody::Form(FormBody { form: key_values(body.get("urlencoded")) })),
+        // Sending multipart fields form-urlencoded would change what the server receives
+        "formdata" => {
+            return Err(AppError::InvalidConfig(format!(
+                "Request '{}' has a form-data (multipart) body, which is not supported; use urlencoded or raw",
+                name
+            )))
+        }
+        "file" => body
+            .pointer("/file/src")
+            .and_then(Value::as_str)
+            .map(|src| RequestBody::File(FileBody::new(src))),
+        "graphql" => body.get("graphql").map(|graphql| {
+            let variables = graphql
+                .get("variables")
+                .and_then(Value::as_str)
+                .and_then(|v| serde_json::from_str::<Value>(v).ok())
+                .unwrap_or(Value::Null);
+            RequestBody::from_json(serde_json::json!({
+                "query": graphql.get("query").cloned().unwrap_or(Value::Null),
+                "variables": variables,
+            }))
+        }),
+        _ => None,
+    };
+    Ok(body)
+}
+
+/// Find the status code asserted by `pm.test` scripts of an item
+pub fn status_from_scripts(events: Option<&Value>) -> Option<u16> {
+    let script: String = events
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter(|event| event.get("listen").and_then(Value::as_str) == Some("test"))
+        .filter_map(|event| event.pointer("/script/exec"))
+        .map(|exec| match exec {
+            Value::Array(lines) => lines.iter().filter_map(Value::as_str).collect::<Vec<_>>().join("\n"),
+            other => value_to_text(other),
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    STATUS_PATTERNS.iter().find_map(|pattern| {
+        Regex::new(pattern)
+            .ok()?
+            .captures(&script)
+            .and_then(|captures| captures.get(1))
+            .and_then(|status| status.as_str().parse().ok())
+    })
+}
+
+fn convert_request(
+    name: String,
+    item: &Value,
+    inherited_auth: Option<&Value>,
+    variables: &HashMap<String, Value>,
+) -> Result<ApiTest, AppError> {
+    let request = item.get("request").unwrap_or(&Value::Null);
+    let (method, url, mut headers) = match request {
+        Value::String(url) => ("GET".to_string(), url.clone(), HashMap::new()),
+        _ => (
+            request.get("method").and_then(Value::as_str).unwrap_or("GET").to_uppercase(),
+            request_url(request.get("url"), variables),
+            key_values(request.get("header")),
+        ),
+    };
+
+    if let Some(authorization) = auth_header(request.get("auth").or(inherited_auth)) {
+        headers.entry("Authorization".to_string()).or_insert(authorization);
+    }
+    let body = request_body(&name, request.get("body"), &headers)?;
+    let expected_status = status_from_scripts(item.get("event")).unwrap_or(200);
+
+    let mut test = create_api_test(name, method, interpolate(&url, variables), expected_status);
+    test.body = body.map(|body| body.interpolate(variables));
+    test.headers = if headers.is_empty() { None } else { Some(interpolate_map(&headers, variables)) };
+    Ok(test)
+}
+
+// Walk folders depth-first, keeping the request order of the collection
+fn collect_items(
+    items: &[Value],
+    prefix: &str,
+    inherited_auth: Option<&Value>,
+    variables: &HashMap<String, Value>,
+    tests: &mut Vec<ApiTest>,
+) -> Result<(), AppError> {
+    for item in items {
+        let name = item.get("name").and_then(Value::as_str).unwrap_or("Unnamed request");
+        let full_name = if prefix.is_empty() { name.to_string() } else { format!("{} / {}", prefix, name) };
+        let auth = item.get("auth").or(inherited_auth);
+
+        match item.get("item").and_then(Value::as_array) {
+            Some(children) => collect_items(children, &full_name, auth, variables, tests)?,
+            None if item.get("request").is_some() => tests.push(convert_request(full_name, item, auth, variables)?),
+            None => {}
+        }
+    }
+    Ok(())
+}
+
+/// Convert a Postman collection (v2.0/v2.1) into an API test suite.
+///
+/// Folders are flattened in order with their names as a prefix. Collection variables and
+/// environment values are substituted; unknown `{{var}}` placeholders are kept so they can be
+/// filled by `extract` rules. Status checks in `pm.test` scripts become `expected_status`.
+/// Form-data (multipart) bodies are rejected.
+pub fn import_postman(collection: &Value, environment: Option<&Value>) -> Result<ApiTestConfig, AppError> {
+    if let Some(schema) = collection.pointer("/info/schema").and_then(Value::as_str) {
+        if !schema.contains("/v2.") {
+            return Err(AppError::InvalidConfig(format!("Unsupported Postman collection schema: {}", schema)));
+        }
+    }
+
+    let items = collection
+        .get("item")
+        .and_then(Value::as_array)
+        .ok_or_else(|| AppError::InvalidConfig("Postman collection has no items".to_string()))?;
+
+    let variables = collect_variables(collection, environment);
+    let mut tests = Vec::new();
+    collect_items(items, "", collection.get("auth"), &variables, &mut tests)?;
+
+    if tests.is_empty() {
+        return Err(AppError::InvalidConfig("Postman collection has no requests".to_string()));
+    }
+    Ok(ApiTestConfig { tests })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_import_postman_collection() {
+        let collection = json!({
+            "info": {"schema": "https://schema.getpostman.com/json/collection/v2.1.0/collection.json"},
+            "variable": [{"key": "base", "value": "https://api.example.com"}, {"key": "user", "value": "7"}],
+            "auth": {"type": "bearer", "bearer": [{"key": "token", "value": "{{token}}"}]},
+            "item": [
+                {
+                    "name": "Users",
+                    "item": [{
+                        "name": "Create user",
+                        "request": {
+                            "method": "POST",
+                            "header": [
+                                {"key": "X-Trace", "value": "1"},
+                                {"key": "X-Debug", "value": "1", "disabled": true}
+                            ],
+                            "url": {"raw": "{{base}}/users"},
+                            "body": {"mode": "raw", "raw": "{\"id\": \"{{user}}\"}", "options": {"raw": {"language": "json"}}}
+                        },
+                        "event": [{"listen": "test", "script": {"exec": ["pm.test('created', () => {", "  pm.response.to.have.status(201);", "});"]}}]
+                    }]
+                },
+                {"name": "Health", "request": "{{base}}/health"},
+                {
+                    "name": "Search",
+                    "request": {
+                        "url": {
+                            "protocol": "https",
+                            "host": ["api", "example", "com"],
+                            "path": ["search"],
+                            "query": [{"key": "q", "value": "a b&c=d"}, {"key": "user", "value": "{{user}}"}, {"key": "next", "value": "{{cursor}}"}]
+                        }
+                    }
+                }
+            ]
+        });
+        let environment = json!({"values": [{"key": "token", "value": "secret", "enabled": true}]});
+
+        let config = import_postman(&collection, Some(&environment)).unwrap();
+        assert_eq!(config.tests.len(), 3);
+
+        let create = &config.tests[0];
+        assert_eq!(create.name, "Users / Create user");
+        assert_eq!(create.url, "https://api.example.com/users");
+        assert_eq!(create.expected_status, 201);
+        let headers = create.headers.as_ref().unwrap();
+        assert_eq!(headers["Authorization"], "Bearer secret");
+        assert!(!headers.contains_key("X-Debug"));
+        assert_eq!(create.body, Some(RequestBody::Json(json!({"id": "7"}))));
+
+        assert_eq!(config.tests[1].method, "GET");
+        assert_eq!(config.tests[1].url, "https://api.example.com/health");
+        assert_eq!(config.tests[1].expected_status, 200);
+        assert_eq!(config.tests[2].url, "https://api.example.com/search?next={{cursor}}&q=a+b%26c%3Dd&user=7");
+
+        let upload = json!({"item": [{
+            "name": "Upload",
+            "request": {"method": "POST", "url": "https://api.example.com/upload", "body": {"mode": "formdata", "formdata": [{"key": "a", "value": "1"}]}}
+        }]});
+        let error = import_postman(&upload, None).unwrap_err().to_string();
+        assert!(error.contains("'Upload'") && error.contains("form-data"), "{}", error);
+    }
+
+    #[test]
+    fn test_status_from_scripts() {
+        let events = json!([{"listen": "test", "script": {"exec": "pm.expect(pm.response.code).to.eql(204);"}}]);
+        assert_eq!(status_from_scripts(Some(&events)), Some(204));
+        let events = json!([{"listen": "test", "script": {"exec": ["tests['ok'] = responseCode.code === 404;"]}}]);
+        assert_eq!(status_from_scripts(Some(&events)), Some(404));
+        assert_eq!(status_from_scripts(None), None);
+    }
+}
@@ -0,0 +1,114 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::model::test::ApiRequestResult;
+use crate::model::utils::fixture::read_fixture_to_string;
+
+/// JSON Schema used to validate a response body, given inline or as a path to a file
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum SchemaSource {
+    File(String),
+    Inline(Value),
+}
+
+impl SchemaSource {
+    /// Load the schema document, reading schema files from the fixtures directory
+    pub async fn load(&self) -> Result<Value, String> {
+        match self {
+            SchemaSource::Inline(schema) => Ok(schema.clone()),
+            SchemaSource::File(path) => {
+                let content = read_fixture_to_string(path)
+                    .await
+                    .map_err(|e| format!("Failed to read schema file {}: {}", path, e))?;
+                serde_json::from_str(&content)
+                    .map_err(|e| format!("Failed to parse schema file {}: {}", path, e))
+            }
+        }
+    }
+}
+
+/// A single JSON Schema validation error
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SchemaViolation {
+    /// JSON pointer to the offending value in the response body, e.g. `/data/0/id`
+    pub instance_path: String,
+    /// JSON pointer to the schema keyword that failed
+    pub schema_path: String,
+    pub message: String,
+}
+
+fn violation(message: String) -> SchemaViolation {
+    SchemaViolation {
+        instance_path: String::new(),
+        schema_path: String::new(),
+        message,
+    }
+}
+
+/// Validate a JSON body against a schema, returning every validation error
+pub fn validate_schema(schema: &Value, instance: &Value) -> Vec<SchemaViolation> {
+    let validator = match jsonschema::validator_for(schema) {
+        Ok(validator) => validator,
+        Err(e) => return vec![violation(format!("Invalid schema: {}", e))],
+    };
+
+    validator
+        .iter_errors(instance)
+        .map(|error| SchemaViolation {
+            instance_path: error.instance_path.to_string(),
+            schema_path: error.schema_path.to_string(),
+            message: error.to_string(),
+        })
+        .collect()
+}
+
+/// Validate an API response body against a schema source; file sources must be loaded beforehand
+pub fn validate_response_schema(source: &SchemaSource, response: &ApiRequestResult) -> Vec<SchemaViolation> {
+    let schema = match source {
+        SchemaSource::Inline(schema) => schema,
+        SchemaSource::File(path) => return vec![violation(format!("Schema file {} was not loaded", path))],
+    };
+
+    match &response.json {
+        Some(body) => validate_schema(schema, body),
+        None => vec![violation("Response body is not valid JSON".to_string())],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_validate_schema_reports_every_error() {
+        let schema = json!({
+            "type": "object",
+            "required": ["id", "email"],
+            "properties": {
+                "id": {"type": "integer"},
+                "tags": {"type": "array", "items": {"type": "string"}}
+            }
+        });
+
+        assert!(validate_schema(&schema, &json!({"id": 1, "email": "a@b.c"})).is_empty());
+
+        let violations = validate_schema(&schema, &json!({"id": "1", "tags": ["a", 2]}));
+        assert_eq!(violations.len(), 3);
+        assert!(violations.iter().any(|v| v.instance_path == "/id"));
+        assert!(violations.iter().any(|v| v.instance_path == "/tags/1"));
+        assert!(violations.iter().any(|v| v.instance_path.is_empty() && v.message.contains("email")));
+    }
+
+    #[tokio::test]
+    async fn test_schema_source_forms() {
+        let source: SchemaSource = serde_json::from_value(json!("schemas/user.json")).unwrap();
+        assert_eq!(source, SchemaSource::File("schemas/user.json".to_string()));
+        assert!(source.load().await.is_err());
+        assert!(SchemaSource::File("/etc/passwd".to_string()).load().await.unwrap_err().contains("fixtures directory"));
+
+        let source: SchemaSource = serde_json::from_value(json!({"type": "object"})).unwrap();
+        assert_eq!(source.load().await.unwrap(), json!({"type": "object"}));
+    }
+}
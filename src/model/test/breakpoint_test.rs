@@ -0,0 +1,209 @@
+use serde::{Deserialize, Serialize};
+
+use crate::model::test::load_test::feeder::FeederConfig;
+use crate::model::test::load_test::sitemap::SitemapSource;
+use crate::model::test::load_test::success::SuccessPolicy;
+use crate::model::test::load_test::think_time::ThinkTime;
+use crate::model::test::stress_test::Stage;
+use crate::model::test::{
+    ArrivalRate, RequestOptions, RequestTemplate, SessionOptions, TestConfig, TestMetrics, WeightedEndpoint,
+};
+
+/// Steps used when `max_steps` is omitted
+pub const DEFAULT_MAX_STEPS: u32 = 10;
+
+/// A stepped stress test that stops at the first load level breaking its SLO
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BreakpointTestConfig {
+    #[serde(default)]
+    pub target_url: String,
+    /// Method, headers, body and query parameters sent to `target_url`
+    #[serde(flatten)]
+    pub request: RequestOptions,
+    /// Ordered requests replayed by each iteration
+    pub requests: Option<Vec<RequestTemplate>>,
+    /// Endpoints picked by weight, one per iteration
+    pub scenario: Option<Vec<WeightedEndpoint>>,
+    /// Sitemap whose URLs are requested, one per iteration
+    pub sitemap: Option<SitemapSource>,
+    /// CSV/NDJSON files whose rows fill `{{column}}` placeholders, one row per iteration
+    pub feeders: Option<Vec<FeederConfig>>,
+    /// Step the arrival rate instead of virtual users; its `rate` is ignored
+    pub arrival_rate: Option<ArrivalRate>,
+    pub think_time: Option<ThinkTime>,
+    pub pacing_ms: Option<u64>,
+    pub session: Option<SessionOptions>,
+    /// Statuses, body content and response time a response needs to count as a success
+    pub success: Option<SuccessPolicy>,
+    /// Virtual users (or iterations per second) of the first step
+    pub start: u32,
+    /// Load added by every following step
+    pub step: u32,
+    /// Number of steps at most, `DEFAULT_MAX_STEPS` when omitted
+    pub max_steps: Option<u32>,
+    /// How long each step is held
+    pub step_duration_secs: u32,
+    pub slo: SloCriteria,
+}
+
+/// Limits every step of a breakpoint test must stay within
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SloCriteria {
+    /// Highest acceptable error rate, in percent
+    pub max_error_rate: Option<f64>,
+    /// Highest acceptable average response time, in ms
+    pub max_average_response_time: Option<f64>,
+    /// Highest acceptable single response time, in ms
+    pub max_response_time: Option<f64>,
+    /// Highest acceptable 95th percentile response time, in ms
+    pub max_p95_response_time: Option<f64>,
+    /// Highest acceptable 99th percentile response time, in ms
+    pub max_p99_response_time: Option<f64>,
+}
+
+impl SloCriteria {
+    /// Describe every limit the metrics exceed
+    pub fn violations(&self, metrics: &TestMetrics) -> Vec<String> {
+        if metrics.requests_completed == 0 {
+            return vec!["no requests completed".to_string()];
+        }
+        let percentiles = metrics.percentiles.unwrap_or_default();
+        let checks = [
+            ("error rate", metrics.error_rate, self.max_error_rate, "%"),
+            ("average response time", metrics.average_response_time, self.max_average_response_time, "ms"),
+            ("max response time", metrics.max_response_time, self.max_response_time, "ms"),
+            ("p95 response time", percentiles.p95, self.max_p95_response_time, "ms"),
+            ("p99 response time", percentiles.p99, self.max_p99_response_time, "ms"),
+        ];
+        checks
+            .iter()
+            .filter_map(|(name, value, limit, unit)| {
+                limit
+                    .filter(|limit| value > limit)
+                    .map(|limit| format!("{} {:.2}{} above {}{}", name, value, unit, limit, unit))
+            })
+            .collect()
+    }
+}
+
+/// Outcome of one load level of a breakpoint test
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BreakpointStep {
+    pub load: u32,
+    pub passed: bool,
+    pub violations: Vec<String>,
+    /// Metrics of the requests completed during this step only
+    pub metrics: TestMetrics,
+}
+
+/// Per-step table of a breakpoint test and the highest load that met the SLO
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BreakpointReport {
+    pub last_passing_load: Option<u32>,
+    /// First load that violated the SLO, `None` when every step passed
+    pub breaking_load: Option<u32>,
+    pub steps: Vec<BreakpointStep>,
+}
+
+impl BreakpointReport {
+    pub fn record(&mut self, step: BreakpointStep) {
+        if step.passed && self.breaking_load.is_none() {
+            self.last_passing_load = Some(step.load);
+        } else if !step.passed {
+            self.breaking_load.get_or_insert(step.load);
+        }
+        self.steps.push(step);
+    }
+}
+
+impl BreakpointTestConfig {
+    pub fn max_steps(&self) -> u32 {
+        self.max_steps.unwrap_or(DEFAULT_MAX_STEPS)
+    }
+
+    /// Load of step `index`, counting from 0
+    pub fn load_at(&self, index: u32) -> u32 {
+        self.start.saturating_add(self.step.saturating_mul(index))
+    }
+
+    /// Stage profile jumping to each step's load and holding it for `step_duration_secs`
+    pub fn stages(&self) -> Vec<Stage> {
+        (0..self.max_steps())
+            .flat_map(|index| {
+                let target = self.load_at(index);
+                [
+                    Stage { duration_secs: 0, target },
+                    Stage { duration_secs: self.step_duration_secs, target },
+                ]
+            })
+            .collect()
+    }
+}
+
+pub fn create_test_config_from_breakpoint(config: &BreakpointTestConfig) -> TestConfig {
+    let stages = config.stages();
+    TestConfig {
+        target_url: config.target_url.clone(),
+        concurrent_users: config.load_at(config.max_steps().saturating_sub(1)),
+        duration_secs: config.step_duration_secs.saturating_mul(config.max_steps()),
+        num_requests: 0,
+        request: config.request.clone(),
+        requests: config.requests.clone(),
+        scenario: config.scenario.clone(),
+        sitemap: config.sitemap.clone(),
+        feeders: config.feeders.clone(),
+        arrival_rate: config.arrival_rate.clone(),
+        stages: Some(stages),
+        think_time: config.think_time.clone(),
+        pacing_ms: config.pacing_ms,
+        session: config.session.clone(),
+        success: config.success.clone(),
+        adaptive: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::histogram::LatencyPercentiles;
+    use serde_json::json;
+
+    #[test]
+    fn test_breakpoint_stages_and_slo() {
+        let config: BreakpointTestConfig = serde_json::from_value(json!({
+            "target_url": "https://api.example.com",
+            "start": 10,
+            "step": 10,
+            "max_steps": 3,
+            "step_duration_secs": 30,
+            "slo": {"max_error_rate": 1.0, "max_average_response_time": 200, "max_p99_response_time": 800}
+        }))
+        .unwrap();
+
+        let test_config = create_test_config_from_breakpoint(&config);
+        assert_eq!(test_config.duration_secs, 90);
+        assert_eq!(test_config.concurrent_users, 30);
+        let stages = test_config.stages.unwrap();
+        assert_eq!(stages.len(), 6);
+        assert_eq!(stages[3], Stage { duration_secs: 30, target: 20 });
+
+        let mut metrics = TestMetrics { requests_completed: 100, average_response_time: 150.0, ..Default::default() };
+        assert!(config.slo.violations(&metrics).is_empty());
+        metrics.error_rate = 2.5;
+        assert_eq!(config.slo.violations(&metrics), vec!["error rate 2.50% above 1%".to_string()]);
+        metrics.percentiles = Some(LatencyPercentiles { p99: 950.0, ..Default::default() });
+        assert_eq!(config.slo.violations(&metrics)[1], "p99 response time 950.00ms above 800ms");
+        assert_eq!(config.slo.violations(&TestMetrics::default()).len(), 1);
+    }
+
+    #[test]
+    fn test_breakpoint_report() {
+        let mut report = BreakpointReport::default();
+        for (load, passed) in [(10, true), (20, true), (30, false)] {
+            report.record(BreakpointStep { load, passed, violations: Vec::new(), metrics: TestMetrics::default() });
+        }
+        assert_eq!(report.last_passing_load, Some(20));
+        assert_eq!(report.breaking_load, Some(30));
+        assert_eq!(report.steps.len(), 3);
+    }
+}
@@ -2,11 +2,81 @@ use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::time::Duration;
 
+use crate::model::test::{RequestOptions, RequestTemplate};
+use feeder::FeederConfig;
+use sitemap::SitemapSource;
+use adaptive::AdaptiveConcurrency;
+use success::SuccessPolicy;
+use think_time::ThinkTime;
+
+pub mod adaptive;
+pub mod feeder;
+pub mod har;
+pub mod sitemap;
+pub mod success;
+pub mod think_time;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LoadTestConfig {
+    #[serde(default)]
     pub target_url: String,
     pub concurrent_users: Option<u32>,
+    /// Number of requests, or of sequence iterations when `requests` is set
     pub num_requests: u32,
+    /// Method, headers, body and query parameters sent to `target_url`
+    #[serde(flatten)]
+    pub request: RequestOptions,
+    /// Ordered requests (e.g. imported from a HAR file) replayed by each iteration
+    pub requests: Option<Vec<RequestTemplate>>,
+    /// Endpoints picked by weight, one per iteration
+    pub scenario: Option<Vec<WeightedEndpoint>>,
+    /// Sitemap whose URLs are requested, one per iteration
+    pub sitemap: Option<SitemapSource>,
+    /// CSV/NDJSON files whose rows fill `{{column}}` placeholders, one row per iteration
+    pub feeders: Option<Vec<FeederConfig>>,
+    /// Start iterations at a fixed rate instead of keeping `concurrent_users` busy
+    pub arrival_rate: Option<ArrivalRate>,
+    /// Pause after each request that sets no `think_time_ms` of its own
+    pub think_time: Option<ThinkTime>,
+    /// Minimum time between the starts of two iterations of the same virtual user
+    pub pacing_ms: Option<u64>,
+    /// Per-virtual-user cookie jar and connection pool
+    pub session: Option<SessionOptions>,
+    /// Statuses, body content and response time a response needs to count as a success
+    pub success: Option<SuccessPolicy>,
+    /// Adjust the number of virtual users to hold a latency or error rate target
+    pub adaptive: Option<AdaptiveConcurrency>,
+}
+
+/// Open-model executor settings: iterations start on schedule regardless of response times
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ArrivalRate {
+    /// Iterations started per second; taken from `stages` when they are set
+    #[serde(default)]
+    pub rate: f64,
+    /// Pending iterations allowed before new ones are dropped, `concurrent_users` when omitted
+    pub max_in_flight: Option<u32>,
+}
+
+/// What each virtual user keeps to itself besides the variables it extracts
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SessionOptions {
+    /// Give every virtual user its own cookie jar
+    #[serde(default)]
+    pub cookies: bool,
+    /// Give every virtual user its own HTTP client and connection pool
+    #[serde(default)]
+    pub connection_per_user: bool,
+}
+
+/// One request of a multi-endpoint load scenario, picked in proportion to its weight
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WeightedEndpoint {
+    /// Label used for the per-endpoint metrics
+    pub name: String,
+    pub weight: u32,
+    #[serde(flatten)]
+    pub request: RequestTemplate,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
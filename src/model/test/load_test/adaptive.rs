@@ -0,0 +1,117 @@
+use serde::{Deserialize, Serialize};
+
+/// Adjustment interval used when `interval_ms` is omitted
+pub const DEFAULT_INTERVAL_MS: u64 = 1000;
+/// Virtual users added per healthy interval when `increase` is omitted
+pub const DEFAULT_INCREASE: u32 = 1;
+/// Share of virtual users kept after an overloaded interval when `decrease_factor` is omitted
+pub const DEFAULT_DECREASE_FACTOR: f64 = 0.75;
+
+/// Additive-increase/multiplicative-decrease control of the number of virtual users, holding
+/// the average response time and error rate of each interval under their targets
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AdaptiveConcurrency {
+    /// Average response time to stay under, in ms
+    pub target_latency_ms: Option<f64>,
+    /// Error rate to stay under, in percent
+    pub max_error_rate: Option<f64>,
+    /// Lowest number of virtual users, 1 when omitted
+    pub min_users: Option<u32>,
+    /// Highest number of virtual users, `concurrent_users` when omitted
+    pub max_users: Option<u32>,
+    /// Virtual users at the start, `min_users` when omitted
+    pub initial_users: Option<u32>,
+    /// How often the number of virtual users is adjusted
+    pub interval_ms: Option<u64>,
+    pub increase: Option<u32>,
+    pub decrease_factor: Option<f64>,
+}
+
+/// Requests completed during one adjustment interval
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct AdaptiveSample {
+    pub requests: u64,
+    pub errors: u64,
+    pub average_response_time: f64,
+}
+
+impl AdaptiveConcurrency {
+    /// Check that there is something to hold and that the bounds make sense
+    pub fn validate(&self, concurrent_users: u32) -> Result<(), String> {
+        if self.target_latency_ms.is_none() && self.max_error_rate.is_none() {
+            return Err("Adaptive concurrency needs target_latency_ms or max_error_rate".to_string());
+        }
+        let positive = |value: Option<f64>| value.is_none_or(|value| value.is_finite() && value > 0.0);
+        if !positive(self.target_latency_ms) || !self.max_error_rate.is_none_or(|rate| (0.0..=100.0).contains(&rate)) {
+            return Err("Adaptive concurrency targets must be positive".to_string());
+        }
+        if !self.decrease_factor.is_none_or(|factor| factor > 0.0 && factor < 1.0) {
+            return Err("Adaptive concurrency decrease_factor must be between 0 and 1".to_string());
+        }
+        if self.interval_ms == Some(0) || self.increase == Some(0) {
+            return Err("Adaptive concurrency interval_ms and increase must be greater than 0".to_string());
+        }
+        let (min, max) = (self.min_users(), self.max_users(concurrent_users));
+        if min == 0 || min > max || !(min..=max).contains(&self.initial_users()) {
+            return Err(format!("Adaptive concurrency needs 0 < min_users <= initial_users <= max_users, got {}..{}", min, max));
+        }
+        Ok(())
+    }
+
+    pub fn min_users(&self) -> u32 {
+        self.min_users.unwrap_or(1)
+    }
+
+    pub fn max_users(&self, concurrent_users: u32) -> u32 {
+        self.max_users.unwrap_or(concurrent_users)
+    }
+
+    pub fn initial_users(&self) -> u32 {
+        self.initial_users.unwrap_or_else(|| self.min_users())
+    }
+
+    pub fn interval_ms(&self) -> u64 {
+        self.interval_ms.unwrap_or(DEFAULT_INTERVAL_MS)
+    }
+
+    /// Virtual users for the next interval: fewer when the sample misses a target, one step more
+    /// otherwise, and unchanged when nothing completed
+    pub fn next_users(&self, current: u32, sample: &AdaptiveSample, max_users: u32) -> u32 {
+        if sample.requests == 0 {
+            return current;
+        }
+        let error_rate = sample.errors as f64 / sample.requests as f64 * 100.0;
+        let overloaded = self.target_latency_ms.is_some_and(|target| sample.average_response_time > target)
+            || self.max_error_rate.is_some_and(|max| error_rate > max);
+        let next = if overloaded {
+            let decreased = (current as f64 * self.decrease_factor.unwrap_or(DEFAULT_DECREASE_FACTOR)) as u32;
+            decreased.min(current.saturating_sub(1))
+        } else {
+            current.saturating_add(self.increase.unwrap_or(DEFAULT_INCREASE))
+        };
+        next.clamp(self.min_users(), max_users)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_adaptive_concurrency() {
+        let adaptive: AdaptiveConcurrency =
+            serde_json::from_str(r#"{"target_latency_ms": 200, "max_error_rate": 5, "min_users": 2}"#).unwrap();
+        assert!(adaptive.validate(50).is_ok());
+        assert!(adaptive.validate(1).is_err());
+
+        let healthy = AdaptiveSample { requests: 100, errors: 1, average_response_time: 120.0 };
+        let slow = AdaptiveSample { average_response_time: 350.0, ..healthy };
+        let failing = AdaptiveSample { errors: 10, ..healthy };
+        assert_eq!(adaptive.next_users(10, &healthy, 50), 11);
+        assert_eq!(adaptive.next_users(50, &healthy, 50), 50);
+        assert_eq!(adaptive.next_users(20, &slow, 50), 15);
+        assert_eq!(adaptive.next_users(3, &failing, 50), 2);
+        assert_eq!(adaptive.next_users(2, &slow, 50), 2);
+        assert_eq!(adaptive.next_users(7, &AdaptiveSample::default(), 50), 7);
+    }
+}
@@ -0,0 +1,220 @@
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use crate::model::error::AppError;
+use crate::model::utils::fixture::read_fixture_to_string;
+
+/// File format of a data feeder
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FeederFormat {
+    /// Comma-separated values with a header row naming the columns
+    Csv,
+    /// One JSON object per line
+    Ndjson,
+}
+
+/// How rows are handed out to iterations
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FeederStrategy {
+    /// Rows in file order, one per iteration
+    #[default]
+    Sequential,
+    /// A random row for every iteration; never runs out
+    Random,
+    /// The same row for every iteration of a virtual user, a different one per user
+    UniquePerUser,
+}
+
+/// What happens once every row has been used
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OnExhausted {
+    /// Start again from the first row
+    #[default]
+    Recycle,
+    /// End the test normally
+    Stop,
+    /// End the test and report it as failed
+    Error,
+}
+
+/// A CSV or NDJSON file whose rows fill `{{column}}` placeholders in request templates
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FeederConfig {
+    pub file: String,
+    /// Inferred from the file extension (`.csv`, otherwise NDJSON) when omitted
+    pub format: Option<FeederFormat>,
+    #[serde(default)]
+    pub strategy: FeederStrategy,
+    #[serde(default)]
+    pub on_exhausted: OnExhausted,
+}
+
+/// A feeder that ran out of rows with `on_exhausted` set to `stop` or `error`
+#[derive(Debug, Clone, PartialEq)]
+pub struct FeederExhausted {
+    pub file: String,
+    pub on_exhausted: OnExhausted,
+}
+
+type Row = HashMap<String, Value>;
+
+/// Parse CSV (header row + records) or NDJSON content into rows
+pub fn parse_rows(content: &str, format: FeederFormat) -> Result<Vec<Row>, String> {
+    match format {
+        FeederFormat::Csv => {
+            let mut reader = csv::Reader::from_reader(content.as_bytes());
+            let headers = reader.headers().map_err(|e| e.to_string())?.clone();
+            reader
+                .records()
+                .map(|record| {
+                    let record = record.map_err(|e| e.to_string())?;
+                    Ok(headers
+                        .iter()
+                        .zip(record.iter())
+                        .map(|(column, value)| (column.to_string(), Value::String(value.to_string())))
+                        .collect())
+                })
+                .collect()
+        }
+        FeederFormat::Ndjson => content
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .map(|(index, line)| match serde_json::from_str::<Value>(line) {
+                Ok(Value::Object(map)) => Ok(map.into_iter().collect()),
+                Ok(_) => Err(format!("Line {} is not a JSON object", index + 1)),
+                Err(e) => Err(format!("Line {}: {}", index + 1, e)),
+            })
+            .collect(),
+    }
+}
+
+/// Rows of one feeder file and the position of its next row
+#[derive(Debug)]
+pub struct Feeder {
+    config: FeederConfig,
+    rows: Vec<Row>,
+    next: AtomicUsize,
+}
+
+impl Feeder {
+    /// Read the feeder file from the fixtures directory and parse it off the async runtime
+    pub async fn load(config: &FeederConfig) -> Result<Self, AppError> {
+        let format = config.format.unwrap_or_else(|| {
+            let is_csv = Path::new(&config.file)
+                .extension()
+                .is_some_and(|ext| ext.eq_ignore_ascii_case("csv"));
+            if is_csv { FeederFormat::Csv } else { FeederFormat::Ndjson }
+        });
+        let content = read_fixture_to_string(&config.file).await?;
+        let rows = tokio::task::spawn_blocking(move || parse_rows(&content, format))
+            .await
+            .map_err(|e| AppError::InvalidConfig(format!("Failed to parse feeder file {}: {}", config.file, e)))?
+            .map_err(|e| AppError::InvalidConfig(format!("Failed to parse feeder file {}: {}", config.file, e)))?;
+        if rows.is_empty() {
+            return Err(AppError::InvalidConfig(format!("Feeder file {} has no rows", config.file)));
+        }
+        Ok(Feeder {
+            config: config.clone(),
+            rows,
+            next: AtomicUsize::new(0),
+        })
+    }
+
+    /// Row for the next iteration of virtual user `user`
+    pub fn next_row(&self, user: usize) -> Result<&Row, FeederExhausted> {
+        let index = match self.config.strategy {
+            FeederStrategy::Random => return Ok(&self.rows[rand::thread_rng().gen_range(0..self.rows.len())]),
+            FeederStrategy::Sequential => self.next.fetch_add(1, Ordering::Relaxed),
+            FeederStrategy::UniquePerUser => user,
+        };
+        match self.rows.get(index) {
+            Some(row) => Ok(row),
+            None if self.config.on_exhausted == OnExhausted::Recycle => Ok(&self.rows[index % self.rows.len()]),
+            None => Err(FeederExhausted {
+                file: self.config.file.clone(),
+                on_exhausted: self.config.on_exhausted,
+            }),
+        }
+    }
+}
+
+/// All feeders of a test, merged into one set of variables per iteration
+#[derive(Debug, Default)]
+pub struct FeederSet {
+    feeders: Vec<Feeder>,
+}
+
+impl FeederSet {
+    pub async fn load(configs: &[FeederConfig]) -> Result<Self, AppError> {
+        let mut feeders = Vec::with_capacity(configs.len());
+        for config in configs {
+            feeders.push(Feeder::load(config).await?);
+        }
+        Ok(FeederSet { feeders })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.feeders.is_empty()
+    }
+
+    /// Variables for the next iteration; later feeders win on duplicate column names
+    pub fn next_vars(&self, user: usize) -> Result<HashMap<String, Value>, FeederExhausted> {
+        let mut vars = HashMap::new();
+        for feeder in &self.feeders {
+            vars.extend(feeder.next_row(user)?.iter().map(|(key, value)| (key.clone(), value.clone())));
+        }
+        Ok(vars)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn feeder(rows: Vec<Row>, strategy: FeederStrategy, on_exhausted: OnExhausted) -> Feeder {
+        Feeder {
+            config: FeederConfig { file: "users.csv".to_string(), format: None, strategy, on_exhausted },
+            rows,
+            next: AtomicUsize::new(0),
+        }
+    }
+
+    #[test]
+    fn test_parse_rows() {
+        let rows = parse_rows("user,term\nalice,\"red, shoes\"\nbob,hat\n", FeederFormat::Csv).unwrap();
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0]["term"], json!("red, shoes"));
+
+        let rows = parse_rows("{\"id\": 1}\n\n{\"id\": 2, \"tags\": [\"a\"]}\n", FeederFormat::Ndjson).unwrap();
+        assert_eq!(rows[1]["id"], json!(2));
+        assert!(parse_rows("[1, 2]", FeederFormat::Ndjson).is_err());
+    }
+
+    #[test]
+    fn test_feeder_strategies() {
+        let rows = parse_rows("id\n1\n2\n", FeederFormat::Csv).unwrap();
+
+        let sequential = feeder(rows.clone(), FeederStrategy::Sequential, OnExhausted::Recycle);
+        let ids: Vec<_> = (0..3).map(|_| sequential.next_row(0).unwrap()["id"].clone()).collect();
+        assert_eq!(ids, vec![json!("1"), json!("2"), json!("1")]);
+
+        let stopping = feeder(rows.clone(), FeederStrategy::Sequential, OnExhausted::Stop);
+        assert!(stopping.next_row(0).is_ok());
+        assert!(stopping.next_row(0).is_ok());
+        assert_eq!(stopping.next_row(0).unwrap_err().on_exhausted, OnExhausted::Stop);
+
+        let per_user = feeder(rows, FeederStrategy::UniquePerUser, OnExhausted::Error);
+        assert_eq!(per_user.next_row(1).unwrap()["id"], json!("2"));
+        assert_eq!(per_user.next_row(1).unwrap()["id"], json!("2"));
+        assert!(per_user.next_row(2).is_err());
+    }
+}
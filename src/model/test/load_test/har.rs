@@ -0,0 +1,358 @@
+use chrono::{DateTime, FixedOffset};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use url::Url;
+
+use crate::model::error::AppError;
+use crate::model::test::{FormBody, RequestBody, RequestTemplate};
+
+// File extensions treated as static assets
+const STATIC_EXTENSIONS: [&str; 16] = [
+    "js", "mjs", "css", "map", "png", "jpg", "jpeg", "gif", "svg", "ico", "webp", "avif",
+    "woff", "woff2", "ttf", "otf",
+];
+
+// Response MIME type prefixes treated as static assets
+const STATIC_MIME_TYPES: [&str; 6] = [
+    "image/", "font/", "text/css", "text/javascript", "application/javascript", "application/font",
+];
+
+// Headers that are connection-specific or recomputed by the client on replay
+const SKIPPED_HEADERS: [&str; 5] = ["host", "content-length", "connection", "accept-encoding", "transfer-encoding"];
+
+#[derive(Debug, Deserialize)]
+struct HarFile {
+    log: HarLog,
+}
+
+#[derive(Debug, Deserialize)]
+struct HarLog {
+    #[serde(default)]
+    entries: Vec<HarEntry>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct HarEntry {
+    started_date_time: String,
+    #[serde(default)]
+    time: f64,
+    request: HarRequest,
+    response: Option<HarResponse>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct HarRequest {
+    method: String,
+    url: String,
+    #[serde(default)]
+    headers: Vec<HarNameValue>,
+    post_data: Option<HarPostData>,
+}
+
+#[derive(Debug, Deserialize)]
+struct HarNameValue {
+    name: String,
+    #[serde(default)]
+    value: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct HarPostData {
+    #[serde(default)]
+    mime_type: String,
+    text: Option<String>,
+    #[serde(default)]
+    params: Vec<HarParam>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct HarParam {
+    name: String,
+    value: Option<String>,
+    file_name: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct HarResponse {
+    content: Option<HarContent>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct HarContent {
+    mime_type: Option<String>,
+}
+
+/// Filters applied when importing a HAR file
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct HarImportOptions {
+    /// Drop scripts, stylesheets, images and fonts
+    #[serde(default)]
+    pub exclude_static: bool,
+    /// Drop requests to hosts other than `domains`
+    #[serde(default)]
+    pub exclude_third_party: bool,
+    /// First-party hosts (subdomains included); defaults to the host of the first request
+    #[serde(default)]
+    pub domains: Vec<String>,
+    /// Keep the recorded gap between requests as `think_time_ms`
+    #[serde(default)]
+    pub think_time: bool,
+}
+
+/// Requests recovered from a HAR file, in the order they were recorded
+#[derive(Debug, Clone, Serialize)]
+pub struct HarImport {
+    pub requests: Vec<RequestTemplate>,
+    /// Number of entries removed by the filters
+    pub skipped: usize,
+    /// Time between the first request start and the last response end, in ms
+    pub recorded_duration_ms: u64,
+}
+
+fn host_of(url: &str) -> Option<String> {
+    Url::parse(url).ok()?.host_str().map(str::to_lowercase)
+}
+
+fn is_first_party(host: &str, domains: &[String]) -> bool {
+    domains.iter().any(|domain| {
+        let domain = domain.to_lowercase();
+        host == domain || host.ends_with(&format!(".{}", domain))
+    })
+}
+
+fn is_static_asset(entry: &HarEntry) -> bool {
+    let by_extension = Url::parse(&entry.request.url)
+        .ok()
+        .and_then(|url| url.path().rsplit_once('.').map(|(_, ext)| ext.to_lowercase()))
+        .is_some_and(|ext| STATIC_EXTENSIONS.contains(&ext.as_str()));
+
+    let by_mime_type = entry
+        .response
+        .as_ref()
+        .and_then(|response| response.content.as_ref())
+        .and_then(|content| content.mime_type.as_deref())
+        .is_some_and(|mime| STATIC_MIME_TYPES.iter().any(|prefix| mime.starts_with(prefix)));
+
+    by_extension || by_mime_type
+}
+
+// Merge recorded headers, dropping HTTP/2 pseudo-headers and hop-by-hop headers
+fn replay_headers(headers: &[HarNameValue]) -> HashMap<String, String> {
+    let mut merged: HashMap<String, String> = HashMap::new();
+    for header in headers {
+        let name = header.name.to_lowercase();
+        if name.starts_with(':') || SKIPPED_HEADERS.contains(&name.as_str()) {
+            continue;
+        }
+        let separator = if name == "cookie" { "; " } else { ", " };
+        merged
+            .entry(name)
+            .and_modify(|existing| {
+                existing.push_str(separator);
+                existing.push_str(&header.value);
+            })
+            .or_insert_with(|| header.value.clone());
+    }
+    merged
+}
+
+fn replay_body(post_data: &HarPostData) -> Option<RequestBody> {
+    match &post_data.text {
+        Some(text) if !text.is_empty() => {
+            if post_data.mime_type.contains("json") {
+                if let Ok(value) = serde_json::from_str::<Value>(text) {
+                    return Some(RequestBody::from_json(value));
+                }
+            }
+            Some(RequestBody::Text(text.clone()))
+        }
+        // File parts of recorded multipart forms cannot be replayed
+        _ if !post_data.params.is_empty() => Some(RequestBody::Form(FormBody {
+            form: post_data
+                .params
+                .iter()
+                .filter(|param| param.file_name.is_none())
+                .map(|param| (param.name.clone(), param.value.clone().unwrap_or_default()))
+                .collect(),
+        })),
+        _ => None,
+    }
+}
+
+fn parse_started(entry: &HarEntry) -> Result<DateTime<FixedOffset>, AppError> {
+    DateTime::parse_from_rfc3339(&entry.started_date_time).map_err(|e| {
+        AppError::InvalidConfig(format!(
+            "Invalid startedDateTime '{}' for {}: {}",
+            entry.started_date_time, entry.request.url, e
+        ))
+    })
+}
+
+/// Convert a HAR 1.2 document into an ordered request sequence for the load/stress engine
+pub fn import_har(har: &Value, options: &HarImportOptions) -> Result<HarImport, AppError> {
+    let har: HarFile = serde_json::from_value(har.clone())
+        .map_err(|e| AppError::InvalidConfig(format!("Invalid HAR document: {}", e)))?;
+
+    let mut entries = har
+        .log
+        .entries
+        .into_iter()
+        .map(|entry| parse_started(&entry).map(|started| (started, entry)))
+        .collect::<Result<Vec<_>, AppError>>()?;
+    if entries.is_empty() {
+        return Err(AppError::InvalidConfig("HAR document has no entries".to_string()));
+    }
+    entries.sort_by_key(|(started, _)| *started);
+
+    let domains = if options.domains.is_empty() {
+        host_of(&entries[0].1.request.url).into_iter().collect()
+    } else {
+        options.domains.clone()
+    };
+
+    let total = entries.len();
+    let kept: Vec<_> = entries
+        .into_iter()
+        .filter(|(_, entry)| !(options.exclude_static && is_static_asset(entry)))
+        .filter(|(_, entry)| {
+            !options.exclude_third_party
+                || host_of(&entry.request.url).is_some_and(|host| is_first_party(&host, &domains))
+        })
+        .collect();
+    if kept.is_empty() {
+        return Err(AppError::InvalidConfig("All HAR entries were excluded by the filters".to_string()));
+    }
+
+    // Offsets in ms from the first kept request: (start, end)
+    let first_start = kept[0].0;
+    let offsets: Vec<(f64, f64)> = kept
+        .iter()
+        .map(|(started, entry)| {
+            let start = (*started - first_start).num_microseconds().unwrap_or(0) as f64 / 1000.0;
+            (start, start + entry.time.max(0.0))
+        })
+        .collect();
+
+    let requests = kept
+        .iter()
+        .enumerate()
+        .map(|(index, (_, entry))| {
+            let headers = replay_headers(&entry.request.headers);
+            let think_time_ms = offsets
+                .get(index + 1)
+                .filter(|_| options.think_time)
+                .map(|(next_start, _)| (next_start - offsets[index].1).max(0.0).round() as u64);
+
+            RequestTemplate {
+                method: entry.request.method.to_uppercase(),
+                url: entry.request.url.clone(),
+                headers: if headers.is_empty() { None } else { Some(headers) },
+                body: entry.request.post_data.as_ref().and_then(replay_body),
+                query_params: None,
+                think_time_ms,
+                extract: None,
+            }
+        })
+        .collect();
+
+    let recorded_duration_ms = offsets.iter().map(|(_, end)| *end).fold(0.0, f64::max).round() as u64;
+
+    Ok(HarImport {
+        requests,
+        skipped: total - kept.len(),
+        recorded_duration_ms,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn sample_har() -> Value {
+        json!({
+            "log": {
+                "version": "1.2",
+                "entries": [
+                    {
+                        "startedDateTime": "2025-03-20T10:00:00.000Z",
+                        "time": 120.0,
+                        "request": {
+                            "method": "GET",
+                            "url": "https://shop.example.com/products",
+                            "headers": [
+                                {"name": ":authority", "value": "shop.example.com"},
+                                {"name": "Cookie", "value": "a=1"},
+                                {"name": "cookie", "value": "b=2"}
+                            ]
+                        },
+                        "response": {"content": {"mimeType": "text/html"}}
+                    },
+                    {
+                        "startedDateTime": "2025-03-20T10:00:00.050Z",
+                        "time": 10.0,
+                        "request": {"method": "GET", "url": "https://shop.example.com/app.js"},
+                        "response": {"content": {"mimeType": "application/javascript"}}
+                    },
+                    {
+                        "startedDateTime": "2025-03-20T10:00:00.060Z",
+                        "time": 30.0,
+                        "request": {"method": "GET", "url": "https://www.google-analytics.com/collect"}
+                    },
+                    {
+                        "startedDateTime": "2025-03-20T10:00:02.120Z",
+                        "time": 80.0,
+                        "request": {
+                            "method": "post",
+                            "url": "https://api.shop.example.com/cart",
+                            "headers": [{"name": "Content-Type", "value": "application/json"}],
+                            "postData": {"mimeType": "application/json", "text": "{\"id\": 7}"}
+                        }
+                    }
+                ]
+            }
+        })
+    }
+
+    #[test]
+    fn test_import_har_with_filters() {
+        let options = HarImportOptions {
+            exclude_static: true,
+            exclude_third_party: true,
+            think_time: true,
+            ..Default::default()
+        };
+        let import = import_har(&sample_har(), &options).unwrap();
+
+        assert_eq!(import.skipped, 2);
+        assert_eq!(import.requests.len(), 2);
+        assert_eq!(import.recorded_duration_ms, 2200);
+
+        let page = &import.requests[0];
+        let headers = page.headers.as_ref().unwrap();
+        assert_eq!(headers["cookie"], "a=1; b=2");
+        assert!(!headers.contains_key(":authority"));
+        assert_eq!(page.think_time_ms, Some(2000));
+
+        let cart = &import.requests[1];
+        assert_eq!(cart.method, "POST");
+        assert_eq!(cart.body, Some(RequestBody::Json(json!({"id": 7}))));
+        assert_eq!(cart.think_time_ms, None);
+    }
+
+    #[test]
+    fn test_import_har_without_filters() {
+        let import = import_har(&sample_har(), &HarImportOptions::default()).unwrap();
+        assert_eq!(import.requests.len(), 4);
+        assert!(import.requests.iter().all(|request| request.think_time_ms.is_none()));
+
+        let empty = json!({"log": {"entries": []}});
+        assert!(import_har(&empty, &HarImportOptions::default()).is_err());
+    }
+}
@@ -0,0 +1,242 @@
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use xml::reader::{EventReader, XmlEvent};
+
+use crate::model::error::AppError;
+
+/// URLs kept when `max_urls` is not set
+pub const DEFAULT_MAX_URLS: usize = 10_000;
+
+// Priority of `<url>` entries without one, as defined by the sitemap protocol
+const DEFAULT_PRIORITY: f64 = 0.5;
+
+// Namespace of the sitemap protocol elements; extensions such as `<image:loc>` use their own
+const SITEMAP_NAMESPACE: &str = "http://www.sitemaps.org/schemas/sitemap/0.9";
+
+/// Order in which sitemap URLs are requested
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SitemapOrder {
+    #[default]
+    RoundRobin,
+    Random,
+    /// Random, weighted by each URL's `<priority>`
+    Priority,
+}
+
+/// Sitemap (or sitemap index) whose URLs are requested instead of `target_url`.
+///
+/// Written either as the sitemap location alone or as an object with options.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(from = "SitemapSourceDef")]
+pub struct SitemapSource {
+    /// URL or local file path of the sitemap
+    pub location: String,
+    pub order: SitemapOrder,
+    /// Maximum number of URLs to keep, `DEFAULT_MAX_URLS` when omitted
+    pub max_urls: Option<usize>,
+    /// Only keep URLs whose path starts with this prefix
+    pub path_prefix: Option<String>,
+    /// Only keep URLs matching this regular expression
+    pub pattern: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum SitemapSourceDef {
+    Location(String),
+    Structured {
+        location: String,
+        #[serde(default)]
+        order: SitemapOrder,
+        #[serde(default)]
+        max_urls: Option<usize>,
+        #[serde(default)]
+        path_prefix: Option<String>,
+        #[serde(default)]
+        pattern: Option<String>,
+    },
+}
+
+impl From<SitemapSourceDef> for SitemapSource {
+    fn from(def: SitemapSourceDef) -> Self {
+        match def {
+            SitemapSourceDef::Location(location) => SitemapSource {
+                location,
+                order: SitemapOrder::default(),
+                max_urls: None,
+                path_prefix: None,
+                pattern: None,
+            },
+            SitemapSourceDef::Structured { location, order, max_urls, path_prefix, pattern } => SitemapSource {
+                location,
+                order,
+                max_urls,
+                path_prefix,
+                pattern,
+            },
+        }
+    }
+}
+
+/// A page listed in a sitemap
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SitemapEntry {
+    pub url: String,
+    pub priority: f64,
+}
+
+/// Content of one sitemap document
+#[derive(Debug, Clone, PartialEq)]
+pub enum Sitemap {
+    /// A `<urlset>` of pages
+    Urls(Vec<SitemapEntry>),
+    /// A `<sitemapindex>` listing nested sitemap locations
+    Index(Vec<String>),
+}
+
+/// Parse a sitemap or sitemap index document.
+///
+/// Only elements of the sitemap namespace count, and `<loc>`/`<priority>` only as direct children
+/// of `<url>` or `<sitemap>`.
+pub fn parse_sitemap(xml: &str) -> Result<Sitemap, AppError> {
+    let mut is_index = false;
+    let mut entries: Vec<SitemapEntry> = Vec::new();
+    let mut nested: Vec<String> = Vec::new();
+    // Local names of the open elements, empty for elements outside the sitemap namespace
+    let mut open_elements: Vec<String> = Vec::new();
+    let mut loc = String::new();
+    let mut priority: Option<f64> = None;
+
+    for event in EventReader::new(xml.as_bytes()) {
+        let event = event.map_err(|e| AppError::InvalidConfig(format!("Invalid sitemap XML: {}", e)))?;
+        match event {
+            XmlEvent::StartElement { name, .. } => {
+                let element = if name.namespace.as_deref() == Some(SITEMAP_NAMESPACE) { name.local_name } else { String::new() };
+                match element.as_str() {
+                    "sitemapindex" if open_elements.is_empty() => is_index = true,
+                    "url" | "sitemap" => {
+                        loc.clear();
+                        priority = None;
+                    }
+                    _ => {}
+                }
+                open_elements.push(element);
+            }
+            XmlEvent::Characters(text) | XmlEvent::CData(text) => {
+                let parent = open_elements.len().checked_sub(2).map(|index| open_elements[index].as_str());
+                match (parent, open_elements.last().map(String::as_str)) {
+                    (Some("url" | "sitemap"), Some("loc")) => loc.push_str(text.trim()),
+                    (Some("url"), Some("priority")) => priority = text.trim().parse().ok(),
+                    _ => {}
+                }
+            }
+            XmlEvent::EndElement { .. } => match open_elements.pop().as_deref() {
+                Some("url") if !loc.is_empty() => entries.push(SitemapEntry {
+                    url: loc.clone(),
+                    priority: priority.unwrap_or(DEFAULT_PRIORITY).clamp(0.0, 1.0),
+                }),
+                Some("sitemap") if !loc.is_empty() => nested.push(loc.clone()),
+                _ => {}
+            },
+            _ => {}
+        }
+    }
+
+    Ok(if is_index { Sitemap::Index(nested) } else { Sitemap::Urls(entries) })
+}
+
+/// Compiled path prefix and pattern filters of a sitemap source
+#[derive(Debug, Clone)]
+pub struct SitemapFilter {
+    path_prefix: Option<String>,
+    pattern: Option<Regex>,
+}
+
+impl SitemapFilter {
+    pub fn new(source: &SitemapSource) -> Result<Self, AppError> {
+        let pattern = source
+            .pattern
+            .as_deref()
+            .map(Regex::new)
+            .transpose()
+            .map_err(|e| AppError::InvalidConfig(format!("Invalid sitemap pattern: {}", e)))?;
+        Ok(SitemapFilter {
+            path_prefix: source.path_prefix.clone(),
+            pattern,
+        })
+    }
+
+    /// Whether a sitemap URL passes the filters
+    pub fn matches(&self, url: &str) -> bool {
+        let prefix_matches = self.path_prefix.as_deref().is_none_or(|prefix| {
+            url::Url::parse(url).is_ok_and(|parsed| parsed.path().starts_with(prefix))
+        });
+        let pattern_matches = self.pattern.as_ref().is_none_or(|pattern| pattern.is_match(url));
+        prefix_matches && pattern_matches
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_sitemap() {
+        let xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+            <urlset xmlns="http://www.sitemaps.org/schemas/sitemap/0.9">
+                <url><loc>https://example.com/</loc><priority>1.0</priority></url>
+                <url><loc> https://example.com/blog/hello </loc></url>
+                <url><loc><![CDATA[https://example.com/docs/intro]]></loc><priority>0.2</priority></url>
+            </urlset>"#;
+        let Sitemap::Urls(entries) = parse_sitemap(xml).unwrap() else {
+            panic!("expected a urlset");
+        };
+        assert_eq!(entries.len(), 3);
+        assert_eq!(entries[0].priority, 1.0);
+        assert_eq!(entries[1].url, "https://example.com/blog/hello");
+        assert_eq!(entries[1].priority, 0.5);
+        assert_eq!(entries[2].url, "https://example.com/docs/intro");
+
+        // Extension elements and elements outside the sitemap namespace are ignored
+        let images = r#"<urlset xmlns="http://www.sitemaps.org/schemas/sitemap/0.9"
+                    xmlns:image="http://www.google.com/schemas/sitemap-image/1.1">
+                <url>
+                    <loc>https://example.com/gallery</loc>
+                    <image:image><image:loc>https://cdn.example.com/photo.jpg</image:loc></image:image>
+                </url>
+                <url><other><loc>https://example.com/nested</loc></other></url>
+            </urlset>"#;
+        let Sitemap::Urls(entries) = parse_sitemap(images).unwrap() else {
+            panic!("expected a urlset");
+        };
+        assert_eq!(entries, vec![SitemapEntry { url: "https://example.com/gallery".to_string(), priority: 0.5 }]);
+        let foreign = r#"<urlset><url><loc>https://example.com/</loc></url></urlset>"#;
+        assert_eq!(parse_sitemap(foreign).unwrap(), Sitemap::Urls(Vec::new()));
+
+        let index = r#"<sitemapindex xmlns="http://www.sitemaps.org/schemas/sitemap/0.9">
+                <sitemap><loc>https://example.com/sitemap-blog.xml</loc></sitemap>
+            </sitemapindex>"#;
+        assert_eq!(
+            parse_sitemap(index).unwrap(),
+            Sitemap::Index(vec!["https://example.com/sitemap-blog.xml".to_string()])
+        );
+    }
+
+    #[test]
+    fn test_sitemap_source_and_filter() {
+        let source: SitemapSource = serde_json::from_str(r#""https://example.com/sitemap.xml""#).unwrap();
+        assert_eq!(source.order, SitemapOrder::RoundRobin);
+
+        let source: SitemapSource = serde_json::from_str(
+            r#"{"location": "sitemap.xml", "order": "priority", "path_prefix": "/blog", "pattern": "hello|world"}"#,
+        )
+        .unwrap();
+        assert_eq!(source.order, SitemapOrder::Priority);
+
+        let filter = SitemapFilter::new(&source).unwrap();
+        assert!(filter.matches("https://example.com/blog/hello"));
+        assert!(!filter.matches("https://example.com/blog/other"));
+        assert!(!filter.matches("https://example.com/docs/hello"));
+    }
+}
@@ -0,0 +1,180 @@
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+use crate::model::error::AppError;
+use crate::model::failure::{ErrorClass, RequestFailure};
+
+/// An inclusive range of HTTP statuses, written as `200`, `"2xx"` or `"200-299"`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "StatusSpec", into = "String")]
+pub struct StatusRange {
+    pub min: u16,
+    pub max: u16,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum StatusSpec {
+    Code(u16),
+    Text(String),
+}
+
+impl TryFrom<StatusSpec> for StatusRange {
+    type Error = String;
+
+    fn try_from(spec: StatusSpec) -> Result<Self, Self::Error> {
+        let text = match spec {
+            StatusSpec::Code(code) if (100..=999).contains(&code) => return Ok(StatusRange { min: code, max: code }),
+            StatusSpec::Code(code) => return Err(format!("Invalid status code {}", code)),
+            StatusSpec::Text(text) => text,
+        };
+        let invalid = || format!("Invalid status range '{}'", text);
+        let parse = |value: &str| value.trim().parse::<u16>().map_err(|_| invalid());
+        let lower = text.to_ascii_lowercase();
+        let range = if let Some(class) = lower.strip_suffix("xx") {
+            // A status class is a single digit, 1xx to 5xx
+            let class = match class {
+                "1" | "2" | "3" | "4" | "5" => parse(class)?,
+                _ => return Err(invalid()),
+            };
+            StatusRange { min: class * 100, max: class * 100 + 99 }
+        } else if let Some((min, max)) = lower.split_once('-') {
+            StatusRange { min: parse(min)?, max: parse(max)? }
+        } else {
+            let code = parse(&lower)?;
+            StatusRange { min: code, max: code }
+        };
+        if range.min > range.max || range.min < 100 || range.max > 999 {
+            return Err(invalid());
+        }
+        Ok(range)
+    }
+}
+
+impl From<StatusRange> for String {
+    fn from(range: StatusRange) -> Self {
+        if range.min == range.max {
+            range.min.to_string()
+        } else {
+            format!("{}-{}", range.min, range.max)
+        }
+    }
+}
+
+impl StatusRange {
+    pub fn contains(&self, status: u16) -> bool {
+        (self.min..=self.max).contains(&status)
+    }
+}
+
+/// What a load/stress response must look like to count as a success
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SuccessPolicy {
+    /// Accepted statuses, any status below 400 when omitted
+    pub expected_status: Option<Vec<StatusRange>>,
+    /// Text the response body must contain
+    pub body_contains: Option<String>,
+    /// Regular expression the response body must match
+    pub body_matches: Option<String>,
+    /// Slower responses count as failures
+    pub max_response_time_ms: Option<u64>,
+}
+
+/// A success policy with its body pattern compiled, applied to every load/stress response
+#[derive(Debug, Clone, Default)]
+pub struct SuccessCriteria {
+    policy: SuccessPolicy,
+    body_matches: Option<Regex>,
+}
+
+impl SuccessCriteria {
+    pub fn new(policy: &SuccessPolicy) -> Result<Self, AppError> {
+        let body_matches = policy
+            .body_matches
+            .as_deref()
+            .map(Regex::new)
+            .transpose()
+            .map_err(|e| AppError::InvalidConfig(format!("Invalid success body_matches pattern: {}", e)))?;
+        Ok(SuccessCriteria {
+            policy: policy.clone(),
+            body_matches,
+        })
+    }
+
+    /// Whether the response body must be read as text to be judged
+    pub fn needs_body(&self) -> bool {
+        self.policy.body_contains.is_some() || self.body_matches.is_some()
+    }
+
+    /// Why a response fails the policy, `None` when it succeeds
+    pub fn check(&self, status: u16, duration: Duration, body: &str) -> Option<RequestFailure> {
+        let status_ok = match &self.policy.expected_status {
+            Some(expected) => expected.iter().any(|range| range.contains(status)),
+            None => status < 400,
+        };
+        if !status_ok {
+            return Some(RequestFailure {
+                class: ErrorClass::from_status(status),
+                message: format!("Unexpected status {}", status),
+            });
+        }
+        let assertion = |message: String| Some(RequestFailure { class: ErrorClass::AssertionFailed, message });
+        if let Some(text) = self.policy.body_contains.as_deref().filter(|text| !body.contains(*text)) {
+            return assertion(format!("Response body does not contain '{}'", text));
+        }
+        if let Some(pattern) = self.body_matches.as_ref().filter(|pattern| !pattern.is_match(body)) {
+            return assertion(format!("Response body does not match '{}'", pattern.as_str()));
+        }
+        let max_ms = self.policy.max_response_time_ms?;
+        if duration > Duration::from_millis(max_ms) {
+            return assertion(format!("Response time {} ms above {} ms", duration.as_millis(), max_ms));
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_status_ranges() {
+        let ranges: Vec<StatusRange> = serde_json::from_str(r#"[200, "3xx", "404-410"]"#).unwrap();
+        assert_eq!(ranges[1], StatusRange { min: 300, max: 399 });
+        assert!(ranges[2].contains(404) && ranges[2].contains(410) && !ranges[2].contains(411));
+        assert!(serde_json::from_str::<StatusRange>(r#""500-400""#).is_err());
+        assert!(serde_json::from_str::<StatusRange>(r#""abc""#).is_err());
+        for class in ["700xx", "655xx", "9xx", "0xx", "xx"] {
+            assert!(StatusRange::try_from(StatusSpec::Text(class.to_string())).is_err(), "{}", class);
+        }
+        for code in ["0", "99", "1000", "\"1000\"", "\"50-200\""] {
+            assert!(serde_json::from_str::<StatusRange>(code).is_err(), "{}", code);
+        }
+        assert_eq!(serde_json::to_string(&ranges[1]).unwrap(), r#""300-399""#);
+    }
+
+    #[test]
+    fn test_success_criteria() {
+        let default = SuccessCriteria::default();
+        assert_eq!(default.check(302, Duration::ZERO, ""), None);
+        let failure = default.check(500, Duration::ZERO, "").unwrap();
+        assert_eq!((failure.class, failure.message.as_str()), (ErrorClass::Http5xx, "Unexpected status 500"));
+
+        let policy: SuccessPolicy = serde_json::from_str(
+            r#"{"expected_status": ["2xx", 404], "body_contains": "ok", "body_matches": "id=\\d+", "max_response_time_ms": 500}"#,
+        )
+        .unwrap();
+        let criteria = SuccessCriteria::new(&policy).unwrap();
+        assert!(criteria.needs_body());
+        assert_eq!(criteria.check(404, Duration::from_millis(10), "ok id=7"), None);
+        assert_eq!(criteria.check(201, Duration::from_millis(10), "failed id=7").unwrap().class, ErrorClass::AssertionFailed);
+        assert_eq!(criteria.check(302, Duration::from_millis(10), "ok id=7").unwrap().class, ErrorClass::AssertionFailed);
+        assert!(criteria.check(200, Duration::from_millis(10), "ok id=x").is_some());
+        assert_eq!(
+            criteria.check(200, Duration::from_millis(800), "ok id=1").unwrap().message,
+            "Response time 800 ms above 500 ms"
+        );
+        assert!(SuccessCriteria::new(&SuccessPolicy { body_matches: Some("(".to_string()), ..Default::default() }).is_err());
+    }
+}
@@ -0,0 +1,100 @@
+use rand::Rng;
+use rand_distr::{Distribution, Exp, Normal};
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// Longest pause and largest distribution parameter, one hour in milliseconds
+pub const MAX_THINK_TIME_MS: f64 = 3_600_000.0;
+
+/// Distribution of the pause a virtual user takes after each request, in milliseconds
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "distribution", rename_all = "snake_case")]
+pub enum ThinkTime {
+    Constant { ms: f64 },
+    Uniform { min_ms: f64, max_ms: f64 },
+    /// Samples below zero are clamped to no pause
+    Normal { mean_ms: f64, std_dev_ms: f64 },
+    Exponential { mean_ms: f64 },
+}
+
+impl ThinkTime {
+    /// Check that the parameters describe a usable distribution, none above `MAX_THINK_TIME_MS`
+    pub fn validate(&self) -> Result<(), String> {
+        let bounded = |ms: f64| ms.is_finite() && ms <= MAX_THINK_TIME_MS;
+        let valid = match *self {
+            ThinkTime::Constant { ms } => bounded(ms) && ms >= 0.0,
+            ThinkTime::Uniform { min_ms, max_ms } => bounded(max_ms) && 0.0 <= min_ms && min_ms <= max_ms,
+            ThinkTime::Normal { mean_ms, std_dev_ms } => bounded(mean_ms) && bounded(std_dev_ms) && std_dev_ms >= 0.0,
+            ThinkTime::Exponential { mean_ms } => bounded(mean_ms) && mean_ms > 0.0,
+        };
+        if valid {
+            Ok(())
+        } else {
+            Err(format!("Invalid think_time parameters: {:?}", self))
+        }
+    }
+
+    /// Draw one pause from the distribution, at most `MAX_THINK_TIME_MS`
+    pub fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> Duration {
+        let ms = match *self {
+            ThinkTime::Constant { ms } => ms,
+            ThinkTime::Uniform { min_ms, max_ms } if min_ms < max_ms => rng.gen_range(min_ms..=max_ms),
+            ThinkTime::Uniform { min_ms, .. } => min_ms,
+            ThinkTime::Normal { mean_ms, std_dev_ms } => Normal::new(mean_ms, std_dev_ms)
+                .map_or(mean_ms, |normal| normal.sample(rng)),
+            ThinkTime::Exponential { mean_ms } => Exp::new(1.0 / mean_ms)
+                .map_or(mean_ms, |exp| exp.sample(rng)),
+        };
+        if ms > 0.0 {
+            Duration::try_from_secs_f64(ms.min(MAX_THINK_TIME_MS) / 1000.0).unwrap_or_default()
+        } else {
+            Duration::ZERO
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    #[test]
+    fn test_think_time_distributions() {
+        let mut rng = StdRng::seed_from_u64(7);
+
+        let constant: ThinkTime = serde_json::from_str(r#"{"distribution": "constant", "ms": 250}"#).unwrap();
+        assert_eq!(constant.sample(&mut rng), Duration::from_millis(250));
+
+        let uniform = ThinkTime::Uniform { min_ms: 100.0, max_ms: 200.0 };
+        assert!((0..100).map(|_| uniform.sample(&mut rng)).all(|pause| {
+            (Duration::from_millis(100)..=Duration::from_millis(200)).contains(&pause)
+        }));
+
+        let normal = ThinkTime::Normal { mean_ms: 10.0, std_dev_ms: 50.0 };
+        assert!((0..100).map(|_| normal.sample(&mut rng)).any(|pause| pause.is_zero()));
+
+        let exponential = ThinkTime::Exponential { mean_ms: 100.0 };
+        let total: Duration = (0..10_000).map(|_| exponential.sample(&mut rng)).sum();
+        let mean_ms = total.as_secs_f64() * 1000.0 / 10_000.0;
+        assert!((90.0..110.0).contains(&mean_ms), "mean was {}", mean_ms);
+    }
+
+    #[test]
+    fn test_think_time_validation() {
+        assert!(ThinkTime::Uniform { min_ms: 10.0, max_ms: 10.0 }.validate().is_ok());
+        assert!(ThinkTime::Uniform { min_ms: 20.0, max_ms: 10.0 }.validate().is_err());
+        assert!(ThinkTime::Normal { mean_ms: 100.0, std_dev_ms: -1.0 }.validate().is_err());
+        assert!(ThinkTime::Exponential { mean_ms: 0.0 }.validate().is_err());
+        assert!(ThinkTime::Constant { ms: 1e300 }.validate().is_err());
+        assert!(ThinkTime::Normal { mean_ms: 100.0, std_dev_ms: f64::MAX }.validate().is_err());
+        assert!(ThinkTime::Uniform { min_ms: 0.0, max_ms: MAX_THINK_TIME_MS }.validate().is_ok());
+
+        // Unvalidated parameters are clamped instead of overflowing a Duration
+        let mut rng = StdRng::seed_from_u64(7);
+        let max = Duration::from_millis(MAX_THINK_TIME_MS as u64);
+        assert_eq!(ThinkTime::Constant { ms: 1e300 }.sample(&mut rng), max);
+        assert_eq!(ThinkTime::Constant { ms: f64::INFINITY }.sample(&mut rng), max);
+        assert_eq!(ThinkTime::Constant { ms: f64::NAN }.sample(&mut rng), Duration::ZERO);
+    }
+}
@@ -1,10 +1,84 @@
 use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+use crate::model::test::{ArrivalRate, RequestOptions, RequestTemplate, SessionOptions, WeightedEndpoint};
+use crate::model::test::load_test::feeder::FeederConfig;
+use crate::model::test::load_test::sitemap::SitemapSource;
+use crate::model::test::load_test::success::SuccessPolicy;
+use crate::model::test::load_test::think_time::ThinkTime;
+use crate::model::test::load_test::adaptive::AdaptiveConcurrency;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct StressTestConfig {
+    #[serde(default)]
     pub target_url: String,
+    /// Peak stage target when omitted with `stages`
+    #[serde(default)]
     pub concurrent_users: u32,
+    /// Total stage duration when omitted with `stages`
+    #[serde(default)]
+    pub duration_secs: u32,
+    /// Method, headers, body and query parameters sent to `target_url`
+    #[serde(flatten)]
+    pub request: RequestOptions,
+    /// Ordered requests (e.g. imported from a HAR file) replayed by each iteration
+    pub requests: Option<Vec<RequestTemplate>>,
+    /// Endpoints picked by weight, one per iteration
+    pub scenario: Option<Vec<WeightedEndpoint>>,
+    /// Sitemap whose URLs are requested, one per iteration
+    pub sitemap: Option<SitemapSource>,
+    /// CSV/NDJSON files whose rows fill `{{column}}` placeholders, one row per iteration
+    pub feeders: Option<Vec<FeederConfig>>,
+    /// Start iterations at a fixed rate instead of keeping `concurrent_users` busy
+    pub arrival_rate: Option<ArrivalRate>,
+    /// Ramp profile for virtual users, or for the arrival rate when `arrival_rate` is set
+    pub stages: Option<Vec<Stage>>,
+    /// Pause after each request that sets no `think_time_ms` of its own
+    pub think_time: Option<ThinkTime>,
+    /// Minimum time between the starts of two iterations of the same virtual user
+    pub pacing_ms: Option<u64>,
+    /// Per-virtual-user cookie jar and connection pool
+    pub session: Option<SessionOptions>,
+    /// Statuses, body content and response time a response needs to count as a success
+    pub success: Option<SuccessPolicy>,
+    /// Adjust the number of virtual users to hold a latency or error rate target
+    pub adaptive: Option<AdaptiveConcurrency>,
+}
+
+/// One segment of a ramp profile: move linearly to `target` over `duration_secs`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Stage {
+    #[serde(alias = "duration")]
     pub duration_secs: u32,
+    /// Virtual users, or iterations per second with `arrival_rate`
+    pub target: u32,
+}
+
+/// Total length of a ramp profile in seconds
+pub fn stages_duration(stages: &[Stage]) -> u32 {
+    stages.iter().map(|stage| stage.duration_secs).sum()
+}
+
+/// Highest target of a ramp profile
+pub fn stages_peak(stages: &[Stage]) -> u32 {
+    stages.iter().map(|stage| stage.target).max().unwrap_or(0)
+}
+
+/// Target `elapsed` into the profile, ramping from the previous stage's target (0 before the
+/// first stage); `None` once every stage has run
+pub fn stage_target(stages: &[Stage], elapsed: Duration) -> Option<f64> {
+    let mut offset = elapsed.as_secs_f64();
+    let mut from = 0.0;
+    for stage in stages {
+        let to = stage.target as f64;
+        let length = stage.duration_secs as f64;
+        if offset < length {
+            return Some(from + (to - from) * offset / length);
+        }
+        offset -= length;
+        from = to;
+    }
+    None
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -22,4 +96,27 @@ pub fn create_test_config_from_stress(config: &StressTestConfig) -> TestConfig {
         duration_secs: config.duration_secs,
         num_requests: 0,
     }
-} 
\ No newline at end of file
+} 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stage_target() {
+        let stages = vec![
+            Stage { duration_secs: 10, target: 100 },
+            Stage { duration_secs: 20, target: 100 },
+            Stage { duration_secs: 0, target: 300 },
+            Stage { duration_secs: 10, target: 0 },
+        ];
+        assert_eq!(stages_duration(&stages), 40);
+        assert_eq!(stages_peak(&stages), 300);
+
+        assert_eq!(stage_target(&stages, Duration::ZERO), Some(0.0));
+        assert_eq!(stage_target(&stages, Duration::from_secs(5)), Some(50.0));
+        assert_eq!(stage_target(&stages, Duration::from_secs(25)), Some(100.0));
+        assert_eq!(stage_target(&stages, Duration::from_secs(30)), Some(300.0));
+        assert_eq!(stage_target(&stages, Duration::from_secs(35)), Some(150.0));
+        assert_eq!(stage_target(&stages, Duration::from_secs(40)), None);
+    }
+}
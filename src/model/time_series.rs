@@ -3,42 +3,57 @@ use chrono::Utc;
 use std::sync::Arc;
 use tokio::sync::Mutex;
 
+use crate::model::histogram::LatencyPercentiles;
+use crate::model::phase::PhaseMetrics;
 use crate::model::test::TestMetrics;
 
-/// Time series data point that matches the frontend's TimeSeriesPoint interface
+/// Time series data point that matches the frontend's TimeSeriesPoint interface.
+///
+/// Every point describes only the requests completed since the previous point.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TimeSeriesPoint {
     pub timestamp: i64,
+    /// Seconds since the test started, at the end of the interval
+    #[serde(default)]
+    pub elapsed_secs: f64,
+    /// Length of the interval, in seconds
+    #[serde(default)]
+    pub interval_secs: f64,
+    /// Requests completed during the interval
+    #[serde(default)]
+    pub requests: u32,
     pub requests_per_second: f64,
     pub average_response_time: f64,
     pub error_rate: f64,
+    /// Response time percentiles, in ms
+    #[serde(default)]
+    pub percentiles: Option<LatencyPercentiles>,
+    /// DNS, connect, TLS, time to first byte and download statistics
+    #[serde(default)]
+    pub phases: Option<PhaseMetrics>,
+    /// Virtual users or arrival rate the engine was aiming for
+    #[serde(default)]
+    pub target_load: Option<f64>,
+    /// Virtual users running iterations
+    #[serde(default)]
+    pub active_users: Option<u64>,
 }
 
 impl TimeSeriesPoint {
-    /// Create a new time series point from test metrics
-    pub fn from_metrics(metrics: &TestMetrics, prev_metrics: Option<&TestMetrics>, elapsed_seconds: f64) -> Self {
-        // Calculate requests per second based on current metrics and previous metrics
-        let rps = if let Some(prev) = prev_metrics {
-            let requests_diff = metrics.requests_completed as f64 - prev.requests_completed as f64;
-            if elapsed_seconds > 0.0 {
-                requests_diff / elapsed_seconds
-            } else {
-                0.0
-            }
-        } else {
-            // If no previous metrics, calculate average based on total test time
-            if elapsed_seconds > 0.0 {
-                metrics.requests_completed as f64 / elapsed_seconds
-            } else {
-                0.0
-            }
-        };
-
+    /// Create a new time series point from the metrics of one interval
+    pub fn from_metrics(metrics: &TestMetrics, elapsed_secs: f64, interval_secs: f64) -> Self {
         Self {
             timestamp: Utc::now().timestamp_millis(),
-            requests_per_second: rps,
+            elapsed_secs,
+            interval_secs,
+            requests: metrics.requests_completed,
+            requests_per_second: metrics.requests_per_second,
             average_response_time: metrics.average_response_time,
             error_rate: metrics.error_rate,
+            percentiles: metrics.percentiles,
+            phases: metrics.phases,
+            target_load: metrics.target_load,
+            active_users: metrics.active_users,
         }
     }
 }
@@ -46,34 +61,38 @@ impl TimeSeriesPoint {
 /// Helper struct to track and generate time series data
 pub struct TimeSeriesTracker {
     points: Arc<Mutex<Vec<TimeSeriesPoint>>>,
-    last_metrics: Arc<Mutex<Option<TestMetrics>>>,
+    // End of the previous interval
+    last_point_time: Arc<Mutex<Option<chrono::DateTime<Utc>>>>,
     start_time: Arc<Mutex<chrono::DateTime<Utc>>>,
 }
 
+impl Default for TimeSeriesTracker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl TimeSeriesTracker {
     /// Create a new time series tracker
     pub fn new() -> Self {
         Self {
             points: Arc::new(Mutex::new(Vec::new())),
-            last_metrics: Arc::new(Mutex::new(None)),
+            last_point_time: Arc::new(Mutex::new(None)),
             start_time: Arc::new(Mutex::new(Utc::now())),
         }
     }
 
-    /// Add a new data point from current metrics
+    /// Add a data point from the metrics of the interval since the previous point
     pub async fn add_point(&self, metrics: &TestMetrics) {
+        let now = Utc::now();
         let start_time = *self.start_time.lock().await;
-        let elapsed = (Utc::now() - start_time).num_seconds() as f64;
-        
-        let prev_metrics = self.last_metrics.lock().await.take();
-        let point = TimeSeriesPoint::from_metrics(metrics, prev_metrics.as_ref(), elapsed);
-        
-        let mut points = self.points.lock().await;
-        points.push(point);
-        
-        *self.last_metrics.lock().await = Some(metrics.clone());
+        let interval_start = self.last_point_time.lock().await.replace(now).unwrap_or(start_time);
+        let seconds = |duration: chrono::TimeDelta| duration.num_microseconds().unwrap_or(0) as f64 / 1_000_000.0;
+
+        let point = TimeSeriesPoint::from_metrics(metrics, seconds(now - start_time), seconds(now - interval_start));
+        self.points.lock().await.push(point);
     }
-    
+
     /// Get all time series points
     pub async fn get_points(&self) -> Vec<TimeSeriesPoint> {
         let points = self.points.lock().await;
@@ -84,7 +103,33 @@ impl TimeSeriesTracker {
     pub async fn reset(&self) {
         let mut points = self.points.lock().await;
         points.clear();
-        *self.last_metrics.lock().await = None;
+        *self.last_point_time.lock().await = None;
         *self.start_time.lock().await = Utc::now();
     }
-} 
\ No newline at end of file
+} 
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn test_add_point_covers_its_own_interval() {
+        let tracker = TimeSeriesTracker::new();
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        tracker.add_point(&TestMetrics { requests_completed: 4, error_rate: 25.0, ..Default::default() }).await;
+        tokio::time::sleep(Duration::from_millis(30)).await;
+        tracker.add_point(&TestMetrics { requests_completed: 6, ..Default::default() }).await;
+
+        let points = tracker.get_points().await;
+        assert_eq!(points.len(), 2);
+        assert_eq!((points[0].requests, points[0].error_rate), (4, 25.0));
+        assert!(points[0].interval_secs >= 0.02);
+        assert!(points[1].interval_secs >= 0.03 && points[1].interval_secs < points[1].elapsed_secs);
+        assert!((points[1].elapsed_secs - points[0].elapsed_secs - points[1].interval_secs).abs() < 1e-3);
+
+        tracker.reset().await;
+        assert!(tracker.get_points().await.is_empty());
+        tracker.add_point(&TestMetrics::default()).await;
+        assert!(tracker.get_points().await[0].interval_secs < 0.02);
+    }
+}
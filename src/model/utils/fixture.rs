@@ -0,0 +1,55 @@
+use std::path::{Component, Path, PathBuf};
+
+use crate::model::config::fixtures_dir;
+use crate::model::error::AppError;
+
+/// Resolve a caller-supplied path inside `dir`.
+///
+/// Only relative paths without `..` are accepted, so a test definition cannot reach files
+/// outside the directory.
+pub fn resolve_in(dir: &Path, path: &str) -> Result<PathBuf, AppError> {
+    let relative = Path::new(path);
+    let confined = !path.is_empty()
+        && relative.components().all(|component| matches!(component, Component::Normal(_) | Component::CurDir));
+    if !confined {
+        return Err(AppError::InvalidConfig(format!(
+            "File path {} must be relative to the fixtures directory and must not contain '..'",
+            path
+        )));
+    }
+    Ok(dir.join(relative))
+}
+
+/// Read a file from the fixtures directory (`FIXTURES_DIR`), refusing symlinks that lead out of it
+pub async fn read_fixture(path: &str) -> Result<Vec<u8>, AppError> {
+    let dir = fixtures_dir();
+    let unreadable = |e: std::io::Error| AppError::InvalidConfig(format!("Failed to read file {}: {}", path, e));
+    let resolved = tokio::fs::canonicalize(resolve_in(&dir, path)?).await.map_err(unreadable)?;
+    let root = tokio::fs::canonicalize(&dir).await.map_err(unreadable)?;
+    if !resolved.starts_with(&root) {
+        return Err(AppError::InvalidConfig(format!("File path {} leaves the fixtures directory", path)));
+    }
+    tokio::fs::read(&resolved).await.map_err(unreadable)
+}
+
+/// Read a UTF-8 text file from the fixtures directory
+pub async fn read_fixture_to_string(path: &str) -> Result<String, AppError> {
+    String::from_utf8(read_fixture(path).await?)
+        .map_err(|_| AppError::InvalidConfig(format!("File {} is not valid UTF-8", path)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_in() {
+        let dir = Path::new("fixtures");
+        assert_eq!(resolve_in(dir, "users/data.csv").unwrap(), dir.join("users/data.csv"));
+        assert_eq!(resolve_in(dir, "./user.json").unwrap(), dir.join("./user.json"));
+        assert!(resolve_in(dir, "../.env").is_err());
+        assert!(resolve_in(dir, "users/../../secret").is_err());
+        assert!(resolve_in(dir, "/etc/passwd").is_err());
+        assert!(resolve_in(dir, "").is_err());
+    }
+}
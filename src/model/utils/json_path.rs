@@ -0,0 +1,135 @@
+use serde_json::Value;
+
+/// A single step of a parsed JSON path
+#[derive(Debug, Clone, PartialEq)]
+pub enum PathSegment {
+    Key(String),
+    Index(i64),
+}
+
+/// Parse a simple JSON path such as `$.data[0].id` or `$['content-type']`.
+///
+/// Supports dotted keys, bracketed quoted keys and (negative) array indexes.
+pub fn parse_path(path: &str) -> Result<Vec<PathSegment>, String> {
+    let path = path.trim();
+    let rest = path
+        .strip_prefix('$')
+        .ok_or_else(|| format!("JSON path must start with '$': {}", path))?;
+
+    let chars: Vec<char> = rest.chars().collect();
+    let mut segments = Vec::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        match chars[i] {
+            '.' => {
+                let start = i + 1;
+                let mut end = start;
+                while end < chars.len() && chars[end] != '.' && chars[end] != '[' {
+                    end += 1;
+                }
+                if end == start {
+                    return Err(format!("Empty key in JSON path: {}", path));
+                }
+                segments.push(PathSegment::Key(chars[start..end].iter().collect()));
+                i = end;
+            }
+            '[' => {
+                let close = chars[i..]
+                    .iter()
+                    .position(|c| *c == ']')
+                    .map(|offset| i + offset)
+                    .ok_or_else(|| format!("Unclosed '[' in JSON path: {}", path))?;
+                let inner: String = chars[i + 1..close].iter().collect();
+                let inner = inner.trim();
+
+                let quoted = (inner.starts_with('\'') && inner.ends_with('\''))
+                    || (inner.starts_with('"') && inner.ends_with('"'));
+                if quoted && inner.len() >= 2 {
+                    segments.push(PathSegment::Key(inner[1..inner.len() - 1].to_string()));
+                } else {
+                    let index = inner
+                        .parse::<i64>()
+                        .map_err(|_| format!("Invalid index '{}' in JSON path: {}", inner, path))?;
+                    segments.push(PathSegment::Index(index));
+                }
+                i = close + 1;
+            }
+            c => return Err(format!("Unexpected '{}' in JSON path: {}", c, path)),
+        }
+    }
+
+    Ok(segments)
+}
+
+/// Look up the value at a JSON path.
+///
+/// The pseudo-key `length` returns the size of an array, string or object
+/// when the value has no real `length` key.
+pub fn select(value: &Value, path: &str) -> Result<Option<Value>, String> {
+    let segments = parse_path(path)?;
+    let mut current = value;
+
+    for (position, segment) in segments.iter().enumerate() {
+        let next = match (segment, current) {
+            (PathSegment::Key(key), Value::Object(map)) if map.contains_key(key) => map.get(key),
+            (PathSegment::Key(key), _) if key == "length" => {
+                let length = match current {
+                    Value::Object(map) => map.len(),
+                    Value::Array(items) => items.len(),
+                    Value::String(text) => text.chars().count(),
+                    _ => return Ok(None),
+                };
+                // A length is a number, so nothing can follow it
+                return Ok((position + 1 == segments.len()).then(|| Value::from(length)));
+            }
+            (PathSegment::Index(index), Value::Array(items)) => {
+                let resolved = if *index < 0 { items.len() as i64 + index } else { *index };
+                usize::try_from(resolved).ok().and_then(|i| items.get(i))
+            }
+            _ => None,
+        };
+
+        match next {
+            Some(value) => current = value,
+            None => return Ok(None),
+        }
+    }
+
+    Ok(Some(current.clone()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_parse_path() {
+        assert_eq!(
+            parse_path("$.data[0]['content-type']").unwrap(),
+            vec![
+                PathSegment::Key("data".to_string()),
+                PathSegment::Index(0),
+                PathSegment::Key("content-type".to_string()),
+            ]
+        );
+        assert_eq!(parse_path("$").unwrap(), vec![]);
+        assert!(parse_path("data.id").is_err());
+        assert!(parse_path("$.items[x]").is_err());
+    }
+
+    #[test]
+    fn test_select() {
+        let value = json!({"data": [{"id": 1}, {"id": 2}], "name": "abc", "meta": {"length": 7}});
+        assert_eq!(select(&value, "$.data[1].id").unwrap(), Some(json!(2)));
+        assert_eq!(select(&value, "$.data[-1].id").unwrap(), Some(json!(2)));
+        assert_eq!(select(&value, "$.data.length").unwrap(), Some(json!(2)));
+        assert_eq!(select(&value, "$.name.length").unwrap(), Some(json!(3)));
+        assert_eq!(select(&value, "$.meta.length").unwrap(), Some(json!(7)));
+        assert_eq!(select(&value, "$.data[5]").unwrap(), None);
+        assert_eq!(select(&value, "$.missing.id").unwrap(), None);
+        assert_eq!(select(&value, "$.data.length.id").unwrap(), None);
+        assert_eq!(select(&value, "$").unwrap(), Some(value.clone()));
+    }
+}
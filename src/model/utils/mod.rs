@@ -1,4 +1,7 @@
+pub mod fixture;
 pub mod formatters;
+pub mod json_path;
+pub mod template;
 
 // Re-export common formatting functions
-pub use formatters::format_duration; 
\ No newline at end of file
+pub use formatters::format_duration;
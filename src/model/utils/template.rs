@@ -0,0 +1,105 @@
+use serde_json::Value;
+use std::collections::HashMap;
+
+/// Render a variable value as text, without quotes for strings
+pub fn value_to_text(value: &Value) -> String {
+    match value {
+        Value::String(text) => text.clone(),
+        other => other.to_string(),
+    }
+}
+
+/// Replace `{{name}}` placeholders with variable values.
+///
+/// Placeholders without a matching variable are left untouched.
+pub fn interpolate(text: &str, vars: &HashMap<String, Value>) -> String {
+    if vars.is_empty() || !text.contains("{{") {
+        return text.to_string();
+    }
+
+    let mut output = String::with_capacity(text.len());
+    let mut rest = text;
+
+    while let Some(start) = rest.find("{{") {
+        output.push_str(&rest[..start]);
+        let after_open = &rest[start + 2..];
+        match after_open.find("}}") {
+            Some(end) => {
+                let name = after_open[..end].trim();
+                match vars.get(name) {
+                    Some(value) => output.push_str(&value_to_text(value)),
+                    None => output.push_str(&rest[start..start + end + 4]),
+                }
+                rest = &after_open[end + 2..];
+            }
+            None => {
+                output.push_str(&rest[start..]);
+                rest = "";
+            }
+        }
+    }
+    output.push_str(rest);
+    output
+}
+
+/// Interpolate every string inside a JSON value.
+///
+/// A string that consists of a single placeholder is replaced by the variable's
+/// JSON value, so `"{{id}}"` can become a number.
+pub fn interpolate_json(value: &Value, vars: &HashMap<String, Value>) -> Value {
+    match value {
+        Value::String(text) => {
+            let trimmed = text.trim();
+            let whole_placeholder = trimmed
+                .strip_prefix("{{")
+                .and_then(|inner| inner.strip_suffix("}}"))
+                .filter(|inner| !inner.contains("{{") && !inner.contains("}}"))
+                .and_then(|inner| vars.get(inner.trim()));
+
+            match whole_placeholder {
+                Some(var) => var.clone(),
+                None => Value::String(interpolate(text, vars)),
+            }
+        }
+        Value::Array(items) => Value::Array(items.iter().map(|item| interpolate_json(item, vars)).collect()),
+        Value::Object(map) => Value::Object(
+            map.iter()
+                .map(|(key, item)| (key.clone(), interpolate_json(item, vars)))
+                .collect(),
+        ),
+        other => other.clone(),
+    }
+}
+
+/// Interpolate every value of a string map
+pub fn interpolate_map(map: &HashMap<String, String>, vars: &HashMap<String, Value>) -> HashMap<String, String> {
+    map.iter()
+        .map(|(key, value)| (key.clone(), interpolate(value, vars)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_interpolate() {
+        let vars = HashMap::from([
+            ("id".to_string(), json!(42)),
+            ("token".to_string(), json!("abc")),
+        ]);
+        assert_eq!(interpolate("/users/{{id}}?t={{ token }}", &vars), "/users/42?t=abc");
+        assert_eq!(interpolate("{{unknown}} and {{id", &vars), "{{unknown}} and {{id");
+    }
+
+    #[test]
+    fn test_interpolate_json_keeps_types() {
+        let vars = HashMap::from([("id".to_string(), json!(42))]);
+        let body = json!({"userId": "{{id}}", "label": "user-{{id}}", "tags": ["{{id}}"]});
+        assert_eq!(
+            interpolate_json(&body, &vars),
+            json!({"userId": 42, "label": "user-42", "tags": [42]})
+        );
+    }
+}
@@ -12,7 +12,9 @@ pub fn format_test_results(summary: Option<&TestMetrics>, duration: Option<Durat
         output.push_str(&format!("Average response time: {:.2} ms\n", summary.average_response_time));
         output.push_str(&format!("Min response time: {:.2} ms\n", summary.min_response_time));
         output.push_str(&format!("Max response time: {:.2} ms\n", summary.max_response_time));
+        output.push_str(&format_percentiles(summary));
         output.push_str(&format!("Error rate: {:.2}%\n", summary.error_rate));
+        output.push_str(&format_error_classes(summary));
         output.push_str(&format!("Requests per second: {:.2}\n", summary.requests_per_second));
         
         output.push_str("\nStatus code distribution:\n");
@@ -28,6 +30,27 @@ pub fn format_test_results(summary: Option<&TestMetrics>, duration: Option<Durat
     output
 }
 
+// One line of response time percentiles, empty before the first response, failed or not
+fn format_percentiles(metrics: &TestMetrics) -> String {
+    metrics.percentiles.map_or_else(String::new, |p| {
+        format!(
+            "Response time percentiles: p50 {:.2} ms, p90 {:.2} ms, p95 {:.2} ms, p99 {:.2} ms, p99.9 {:.2} ms\n",
+            p.p50, p.p90, p.p95, p.p99, p.p999
+        )
+    })
+}
+
+// One line of failed requests per error class, most frequent first, empty without errors
+fn format_error_classes(metrics: &TestMetrics) -> String {
+    if metrics.error_classes.is_empty() {
+        return String::new();
+    }
+    let mut classes: Vec<_> = metrics.error_classes.iter().collect();
+    classes.sort_by(|a, b| b.1.cmp(a.1).then_with(|| a.0.to_string().cmp(&b.0.to_string())));
+    let parts: Vec<String> = classes.iter().map(|(class, count)| format!("{} {}", count, class)).collect();
+    format!("Errors by class: {}\n", parts.join(", "))
+}
+
 /// Format metrics for API response
 pub fn format_metrics(metrics: &TestMetrics) -> String {
     let mut output = String::new();
@@ -36,7 +59,9 @@ pub fn format_metrics(metrics: &TestMetrics) -> String {
     output.push_str(&format!("Average response time: {:.2} ms\n", metrics.average_response_time));
     output.push_str(&format!("Min response time: {:.2} ms\n", metrics.min_response_time));
     output.push_str(&format!("Max response time: {:.2} ms\n", metrics.max_response_time));
+    output.push_str(&format_percentiles(metrics));
     output.push_str(&format!("Error rate: {:.2}%\n", metrics.error_rate));
+    output.push_str(&format_error_classes(metrics));
     output.push_str(&format!("Requests per second: {:.2}\n", metrics.requests_per_second));
     
     output.push_str("\nStatus code distribution:\n");
@@ -62,7 +87,9 @@ pub fn format_test_result(result: &TestResult) -> String {
         output.push_str(&format!("Average response time: {:.2} ms\n", metrics.average_response_time));
         output.push_str(&format!("Min response time: {:.2} ms\n", metrics.min_response_time));
         output.push_str(&format!("Max response time: {:.2} ms\n", metrics.max_response_time));
+        output.push_str(&format_percentiles(metrics));
         output.push_str(&format!("Error rate: {:.2}%\n", metrics.error_rate));
+        output.push_str(&format_error_classes(metrics));
         output.push_str(&format!("Requests per second: {:.2}\n", metrics.requests_per_second));
         
         output.push_str("\nStatus codes:\n");
@@ -84,6 +111,7 @@ pub fn format_test_type(test_type: TestType) -> &'static str {
         TestType::Load => "Load Test",
         TestType::Stress => "Stress Test",
         TestType::Api => "API Test",
+        TestType::Breakpoint => "Breakpoint Test",
     }
 }
 